@@ -2,121 +2,1097 @@ extern crate proc_macro;
 
 use proc_macro::TokenStream;
 
-use proc_macro2::{Ident, TokenStream as TokenStream2};
-use quote::quote;
-use syn::{parse_macro_input, Data, DeriveInput, Field, Fields, LitStr};
+use proc_macro2::{Ident, Span, TokenStream as TokenStream2, TokenTree};
+use quote::{quote, quote_spanned, ToTokens};
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::{
+    parse_macro_input, parse_quote, Attribute, Data, DeriveInput, Field, Fields, Generics, LitStr,
+    Token, Variant, WherePredicate,
+};
 
 #[proc_macro_derive(LoadEnv, attributes(econf))]
 pub fn load_env(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
 
-    let name = input.ident;
-    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
-    let content = content(&name, &input.data);
+    let name = &input.ident;
+    let (attrs, generics, Content { load, visit }) =
+        match container_attrs(&input.attrs).and_then(|attrs| {
+            let generics = generics(&input, &attrs)?;
+            let content = content(name, &input.data, &attrs)?;
+            Ok((attrs, generics, content))
+        }) {
+            Ok(v) => v,
+            Err(e) => return e.to_compile_error().into(),
+        };
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    // Structs are recorded as subtrees so that the report can tell the untouched ones.
+    let (load, subtree) = match &input.data {
+        Data::Struct(_) => (
+            quote!(loader.load_subtree(path, |loader| { #load })),
+            Some(quote!(
+                const SUBTREE: bool = true;
+            )),
+        ),
+        _ => (load, None),
+    };
+
+    let load = match &attrs.validate {
+        Some(validate) => quote! {
+            let value = { #load };
+            loader.validate(value, path, #validate)
+        },
+        None => load,
+    };
+
+    let visit = visit.map(|visit| {
+        quote! {
+            fn visit(path: &str, loader: &mut ::econf::Loader) {
+                #visit
+            }
+        }
+    });
+
+    // Structs list the environment variables of their fields for help screens and such.
+    let env_names = matches!(input.data, Data::Struct(_)).then(|| {
+        quote! {
+            impl #impl_generics #name #ty_generics #where_clause {
+                /// Returns the environment variables of the fields under `prefix`, paired with the paths of the fields.
+                /// See `econf::Loader::env_names`.
+                pub fn env_names(prefix: &str) -> ::std::vec::Vec<(::std::string::String, ::std::string::String)> {
+                    ::econf::Loader::new().env_names::<Self>(prefix)
+                }
+            }
+        }
+    });
 
     let expanded = quote! {
         impl #impl_generics ::econf::LoadEnv for #name #ty_generics #where_clause {
+            #subtree
+
             fn load(self, path: &str, loader: &mut ::econf::Loader) -> Self {
-                #content
+                #load
             }
+
+            #visit
         }
+
+        #env_names
     };
 
     TokenStream::from(expanded)
 }
 
-fn is_skip(f: &Field) -> bool {
-    f.attrs.iter().any(|attr| {
-        if attr.path().is_ident("econf") {
-            if let Ok(args) = attr.parse_args::<Ident>() {
-                return args == "skip";
+/// The generics of the impl, with the bounds given by `bound` or generated from the fields.
+///
+/// The generated bounds require `LoadEnv` of each field type that contains a type parameter
+/// and is loaded by `LoadEnv::load`.
+fn generics(input: &DeriveInput, container: &ContainerAttrs) -> syn::Result<Generics> {
+    let mut generics = input.generics.clone();
+    let params: Vec<_> = generics.type_params().map(|p| p.ident.clone()).collect();
+
+    let bounds = match &container.bound {
+        Some(bound) => bound.clone(),
+        None => {
+            let fields = match &input.data {
+                Data::Struct(data) => data.fields.iter().collect(),
+                _ => vec![],
+            };
+            let mut bounds = vec![];
+            for f in fields {
+                let attrs = field_attrs(f, container)?;
+                if !attrs.skip
+                    && attrs.loads_by_trait()
+                    && mentions(f.ty.to_token_stream(), &params)
+                {
+                    let ty = &f.ty;
+                    bounds.push(parse_quote!(#ty: ::econf::LoadEnv));
+                }
             }
+            bounds
         }
+    };
 
-        false
+    generics.make_where_clause().predicates.extend(bounds);
+    Ok(generics)
+}
+
+/// Checks if `tokens` contain any of `idents`.
+fn mentions(tokens: TokenStream2, idents: &[Ident]) -> bool {
+    tokens.into_iter().any(|t| match t {
+        TokenTree::Ident(ident) => idents.contains(&ident),
+        TokenTree::Group(group) => mentions(group.stream(), idents),
+        _ => false,
     })
 }
 
-fn find_renaming(f: &Field) -> Option<String> {
-    let mut rename = None;
-    for attr in &f.attrs {
+#[derive(Default)]
+struct ContainerAttrs {
+    verbatim: bool,
+    case_preserve: bool,
+    by_index_or_name: bool,
+    ascii_case_insensitive: bool,
+    separator: Option<LitStr>,
+    strip_prefix: Option<String>,
+    rename_all: Option<RenameRule>,
+    bound: Option<Vec<WherePredicate>>,
+    validate: Option<syn::Path>,
+}
+
+/// A convention of names given by `rename_all`, applied to snake-case field names.
+#[derive(Clone, Copy)]
+enum RenameRule {
+    Lower,
+    Upper,
+    Pascal,
+    Camel,
+    Snake,
+    ScreamingSnake,
+    Kebab,
+    ScreamingKebab,
+}
+
+impl RenameRule {
+    const NAMES: &'static [&'static str] = &[
+        "\"lowercase\"",
+        "\"UPPERCASE\"",
+        "\"PascalCase\"",
+        "\"camelCase\"",
+        "\"snake_case\"",
+        "\"SCREAMING_SNAKE_CASE\"",
+        "\"kebab-case\"",
+        "\"SCREAMING-KEBAB-CASE\"",
+    ];
+
+    fn parse(s: &str) -> Option<Self> {
+        Some(match s {
+            "lowercase" => Self::Lower,
+            "UPPERCASE" => Self::Upper,
+            "PascalCase" => Self::Pascal,
+            "camelCase" => Self::Camel,
+            "snake_case" => Self::Snake,
+            "SCREAMING_SNAKE_CASE" => Self::ScreamingSnake,
+            "kebab-case" => Self::Kebab,
+            "SCREAMING-KEBAB-CASE" => Self::ScreamingKebab,
+            _ => return None,
+        })
+    }
+
+    fn apply(self, name: &str) -> String {
+        match self {
+            Self::Lower | Self::Snake => name.to_owned(),
+            Self::Upper | Self::ScreamingSnake => name.to_uppercase(),
+            Self::Pascal => name
+                .split('_')
+                .map(|word| {
+                    let mut chars = word.chars();
+                    chars
+                        .next()
+                        .map(|c| c.to_uppercase().chain(chars).collect())
+                        .unwrap_or_default()
+                })
+                .collect::<Vec<String>>()
+                .concat(),
+            Self::Camel => {
+                let pascal = Self::Pascal.apply(name);
+                let mut chars = pascal.chars();
+                chars
+                    .next()
+                    .map(|c| c.to_lowercase().chain(chars).collect())
+                    .unwrap_or_default()
+            }
+            Self::Kebab => name.replace('_', "-"),
+            Self::ScreamingKebab => name.to_uppercase().replace('_', "-"),
+        }
+    }
+}
+
+fn container_attrs(attrs: &[Attribute]) -> syn::Result<ContainerAttrs> {
+    let mut container = ContainerAttrs::default();
+    for attr in attrs {
         if attr.path().is_ident("econf") {
             attr.parse_nested_meta(|meta| {
-                if meta.path.is_ident("rename") {
+                if meta.path.is_ident("verbatim") {
+                    container.verbatim = true;
+                } else if meta.path.is_ident("by_index_or_name") {
+                    container.by_index_or_name = true;
+                } else if meta.path.is_ident("ascii_case_insensitive") {
+                    container.ascii_case_insensitive = true;
+                } else if meta.path.is_ident("case") {
+                    let s: LitStr = meta.value()?.parse()?;
+                    if s.value() != "preserve" {
+                        return Err(syn::Error::new(s.span(), "expected `case = \"preserve\"`"));
+                    }
+                    container.verbatim = true;
+                    container.case_preserve = true;
+                } else if meta.path.is_ident("separator") {
+                    let s: LitStr = meta.value()?.parse()?;
+                    if s.value().is_empty() {
+                        return Err(syn::Error::new(s.span(), "`separator` must not be empty"));
+                    }
+                    container.separator = Some(s);
+                } else if meta.path.is_ident("rename_all") {
+                    let s: LitStr = meta.value()?.parse()?;
+                    container.rename_all =
+                        Some(RenameRule::parse(&s.value()).ok_or_else(|| {
+                            syn::Error::new(
+                                s.span(),
+                                format!(
+                                    "unknown `rename_all` rule, expected one of {}",
+                                    RenameRule::NAMES.join(", ")
+                                ),
+                            )
+                        })?);
+                } else if meta.path.is_ident("strip_prefix") {
+                    let s: LitStr = meta.value()?.parse()?;
+                    container.strip_prefix = Some(s.value());
+                } else if meta.path.is_ident("bound") {
+                    let s: LitStr = meta.value()?.parse()?;
+                    let bound =
+                        s.parse_with(Punctuated::<WherePredicate, Token![,]>::parse_terminated)?;
+                    container.bound = Some(bound.into_iter().collect());
+                } else if meta.path.is_ident("validate") {
                     let s: LitStr = meta.value()?.parse()?;
-                    rename = Some(s.value());
+                    container.validate = Some(s.parse()?);
+                } else {
+                    return Err(meta.error("unsupported econf attribute"));
                 }
 
                 Ok(())
-            })
-            .expect("failed to parse nested meta");
+            })?;
         }
     }
 
-    rename
+    Ok(container)
 }
 
-fn content(name: &Ident, data: &Data) -> TokenStream2 {
-    match data {
-        Data::Struct(data) => match &data.fields {
-            Fields::Named(fields) => {
-                let fields = fields.named.iter().map(|f| {
-                    let ident = &f.ident;
-                    if is_skip(f) {
-                        return quote! {
-                            #ident: self.#ident,
-                        };
+#[derive(Default)]
+struct FieldAttrs {
+    skip: bool,
+    flatten: bool,
+    rename: Option<String>,
+    prefix: Option<LitStr>,
+    absolute: bool,
+    prefixes: Vec<String>,
+    duration_float_secs: bool,
+    serde_duration: bool,
+    yaml: bool,
+    nonzero: bool,
+    bytesize: bool,
+    human_number: bool,
+    ipv6_scope: bool,
+    bind_alias: bool,
+    secret: bool,
+    skip_if_empty: bool,
+    expand_vars: Option<bool>,
+    verbatim: bool,
+    delimiter: Option<String>,
+    kv_sep: Option<LitStr>,
+    humantime: Option<Span>,
+    example: Option<String>,
+    keyed: bool,
+    indexed: bool,
+    sparse: Option<Span>,
+    ci_keys: bool,
+    nested_keys: bool,
+    sep: Option<LitStr>,
+    from_str: bool,
+    shell_words: bool,
+    parse_with: Option<syn::Path>,
+    default: Option<LitStr>,
+    warn_if_default: bool,
+    required: bool,
+}
+
+impl FieldAttrs {
+    /// Checks if the field is loaded by `LoadEnv::load` rather than a parser given by attributes.
+    fn loads_by_trait(&self) -> bool {
+        !(self.duration_float_secs
+            || self.serde_duration
+            || self.yaml
+            || self.nonzero
+            || self.bytesize
+            || self.human_number
+            || self.ipv6_scope
+            || self.bind_alias
+            || self.expand_vars.is_some()
+            || self.delimiter.is_some()
+            || self.keyed
+            || self.indexed
+            || self.ci_keys
+            || self.nested_keys
+            || self.from_str
+            || self.shell_words
+            || self.parse_with.is_some())
+    }
+}
+
+fn field_attrs(f: &Field, container: &ContainerAttrs) -> syn::Result<FieldAttrs> {
+    let mut attrs = FieldAttrs {
+        verbatim: container.verbatim,
+        ..Default::default()
+    };
+    for attr in &f.attrs {
+        if attr.path().is_ident("econf") {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("skip") {
+                    attrs.skip = true;
+                } else if meta.path.is_ident("flatten") {
+                    attrs.flatten = true;
+                } else if meta.path.is_ident("prefixes") {
+                    let input = meta.value()?;
+                    let content;
+                    let brackets = syn::bracketed!(content in input);
+                    let prefixes = Punctuated::<LitStr, Token![,]>::parse_terminated(&content)?;
+                    if prefixes.is_empty() {
+                        return Err(syn::Error::new(
+                            brackets.span.join(),
+                            "`prefixes` must not be empty",
+                        ));
                     }
-                    match find_renaming(f) {
-                        Some(overwritten_name) => quote! {
-                            #ident: self.#ident.load(&(path.to_owned() + "_" + #overwritten_name), loader),
-                        },
-                        None => quote! {
-                            #ident: self.#ident.load(&(path.to_owned() + "_" + stringify!(#ident)), loader),
+                    attrs.prefixes = prefixes.iter().map(|s| s.value()).collect();
+                } else if meta.path.is_ident("rename") {
+                    let s: LitStr = meta.value()?.parse()?;
+                    if s.value().trim_matches('_').is_empty() {
+                        return Err(syn::Error::new(s.span(), "`rename` must not be empty"));
+                    }
+                    attrs.rename = Some(s.value());
+                } else if meta.path.is_ident("prefix") {
+                    let s: LitStr = meta.value()?.parse()?;
+                    if s.value().trim_matches('_').is_empty() {
+                        return Err(syn::Error::new(s.span(), "`prefix` must not be empty"));
+                    }
+                    attrs.prefix = Some(s);
+                } else if meta.path.is_ident("absolute") {
+                    attrs.absolute = true;
+                } else if meta.path.is_ident("duration_float_secs") {
+                    attrs.duration_float_secs = true;
+                } else if meta.path.is_ident("serde_duration") {
+                    attrs.serde_duration = true;
+                } else if meta.path.is_ident("yaml") {
+                    attrs.yaml = true;
+                } else if meta.path.is_ident("nonzero") {
+                    attrs.nonzero = true;
+                } else if meta.path.is_ident("bytesize") {
+                    attrs.bytesize = true;
+                } else if meta.path.is_ident("human_number") {
+                    attrs.human_number = true;
+                } else if meta.path.is_ident("expand_vars") {
+                    let strict = if meta.input.peek(Token![=]) {
+                        let s: LitStr = meta.value()?.parse()?;
+                        if s.value() != "strict" {
+                            return Err(syn::Error::new(
+                                s.span(),
+                                "expected `expand_vars` or `expand_vars = \"strict\"`",
+                            ));
                         }
+                        true
+                    } else {
+                        false
+                    };
+                    attrs.expand_vars = Some(strict);
+                } else if meta.path.is_ident("secret") {
+                    attrs.secret = true;
+                } else if meta.path.is_ident("skip_if_empty") {
+                    attrs.skip_if_empty = true;
+                } else if meta.path.is_ident("ipv6_scope") {
+                    attrs.ipv6_scope = true;
+                } else if meta.path.is_ident("bind_alias") {
+                    attrs.bind_alias = true;
+                } else if meta.path.is_ident("verbatim") {
+                    attrs.verbatim = true;
+                } else if meta.path.is_ident("delimiter") {
+                    let delimiter = if meta.input.peek(Token![=]) {
+                        meta.value()?.parse::<LitStr>()?.value()
+                    } else {
+                        ",".into()
+                    };
+                    attrs.delimiter = Some(delimiter);
+                } else if meta.path.is_ident("default") {
+                    if attrs.default.is_some() {
+                        return Err(meta.error("`default` cannot be used with `default_true`"));
+                    }
+                    attrs.default = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("default_true") {
+                    if attrs.default.is_some() {
+                        return Err(meta.error("`default_true` cannot be used with `default`"));
+                    }
+                    attrs.default = Some(LitStr::new("true", meta.path.span()));
+                } else if meta.path.is_ident("required") {
+                    attrs.required = true;
+                } else if meta.path.is_ident("warn_if_default") {
+                    attrs.warn_if_default = true;
+                } else if meta.path.is_ident("from_str") {
+                    attrs.from_str = true;
+                } else if meta.path.is_ident("shell_words") {
+                    attrs.shell_words = true;
+                } else if meta.path.is_ident("parse_with") {
+                    let s: LitStr = meta.value()?.parse()?;
+                    attrs.parse_with = Some(s.parse()?);
+                } else if meta.path.is_ident("keyed") {
+                    attrs.keyed = true;
+                } else if meta.path.is_ident("indexed") {
+                    attrs.indexed = true;
+                } else if meta.path.is_ident("sparse") {
+                    attrs.sparse = Some(meta.path.span());
+                } else if meta.path.is_ident("ci_keys") {
+                    attrs.ci_keys = true;
+                } else if meta.path.is_ident("nested_keys") {
+                    attrs.nested_keys = true;
+                } else if meta.path.is_ident("sep") {
+                    let s: LitStr = meta.value()?.parse()?;
+                    if s.value().is_empty() {
+                        return Err(syn::Error::new(s.span(), "`sep` must not be empty"));
                     }
+                    attrs.sep = Some(s);
+                } else if meta.path.is_ident("humantime") {
+                    attrs.humantime = Some(meta.path.span());
+                } else if meta.path.is_ident("kv_sep") {
+                    attrs.kv_sep = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("example") {
+                    let s: LitStr = meta.value()?.parse()?;
+                    attrs.example = Some(s.value());
+                } else {
+                    return Err(meta.error("unsupported econf attribute"));
+                }
+
+                Ok(())
+            })?;
+        }
+    }
+
+    if let (false, Some(sep)) = (attrs.nested_keys, &attrs.sep) {
+        return Err(syn::Error::new(sep.span(), "`sep` requires `nested_keys`"));
+    }
+
+    if let (false, Some(span)) = (attrs.indexed, attrs.sparse) {
+        return Err(syn::Error::new(span, "`sparse` requires `indexed`"));
+    }
+
+    if let (None, Some(kv_sep)) = (&attrs.delimiter, &attrs.kv_sep) {
+        return Err(syn::Error::new(
+            kv_sep.span(),
+            "`kv_sep` requires `delimiter`",
+        ));
+    }
+
+    if let Some(span) = attrs.humantime {
+        if attrs.delimiter.is_none() {
+            return Err(syn::Error::new(span, "`humantime` requires `delimiter`"));
+        }
+        if attrs.kv_sep.is_some() {
+            return Err(syn::Error::new(
+                span,
+                "`humantime` cannot be used with `kv_sep`",
+            ));
+        }
+    }
+
+    // `nonzero` validates a duration parsed by either `Duration` or `duration_float_secs`.
+    if attrs.nonzero
+        && (attrs.serde_duration
+            || attrs.yaml
+            || attrs.bytesize
+            || attrs.human_number
+            || attrs.ipv6_scope
+            || attrs.bind_alias
+            || attrs.expand_vars.is_some()
+            || attrs.delimiter.is_some()
+            || attrs.keyed
+            || attrs.indexed
+            || attrs.ci_keys
+            || attrs.nested_keys
+            || attrs.from_str
+            || attrs.shell_words
+            || attrs.parse_with.is_some())
+    {
+        return Err(syn::Error::new_spanned(
+            f,
+            "`nonzero` can only be used with `duration_float_secs` among parsers",
+        ));
+    }
+
+    if attrs.flatten && !attrs.prefixes.is_empty() {
+        return Err(syn::Error::new_spanned(
+            f,
+            "`flatten` cannot be used with `prefixes`",
+        ));
+    }
+
+    if let Some(prefix) = &attrs.prefix {
+        if attrs.rename.is_some() {
+            return Err(syn::Error::new(
+                prefix.span(),
+                "`prefix` cannot be used with `rename`",
+            ));
+        }
+        if attrs.flatten {
+            return Err(syn::Error::new(
+                prefix.span(),
+                "`prefix` cannot be used with `flatten`",
+            ));
+        }
+    }
+
+    if attrs.absolute {
+        if attrs.prefix.is_none() {
+            return Err(syn::Error::new_spanned(f, "`absolute` requires `prefix`"));
+        }
+        if !attrs.prefixes.is_empty() {
+            return Err(syn::Error::new_spanned(
+                f,
+                "`absolute` cannot be used with `prefixes`",
+            ));
+        }
+    }
+
+    if attrs.flatten && attrs.rename.is_some() {
+        return Err(syn::Error::new_spanned(
+            f,
+            "`flatten` cannot be used with `rename`",
+        ));
+    }
+
+    if let (true, Some(default)) = (attrs.skip, &attrs.default) {
+        return Err(syn::Error::new(
+            default.span(),
+            "`default` cannot be used with `skip`",
+        ));
+    }
+
+    if let (true, Some(default)) = (attrs.required, &attrs.default) {
+        return Err(syn::Error::new(
+            default.span(),
+            "`default` cannot be used with `required`",
+        ));
+    }
+
+    if let (true, Some(default)) = (attrs.flatten, &attrs.default) {
+        return Err(syn::Error::new(
+            default.span(),
+            "`default` cannot be used with `flatten`",
+        ));
+    }
+
+    if attrs.required && attrs.skip {
+        return Err(syn::Error::new_spanned(
+            f,
+            "`required` cannot be used with `skip`",
+        ));
+    }
+
+    Ok(attrs)
+}
+
+/// The name given by `#[econf(rename = "...")]` on an enum variant, which is the only attribute of variants.
+fn variant_rename(v: &Variant) -> syn::Result<Option<LitStr>> {
+    let mut rename = None;
+    for attr in &v.attrs {
+        if attr.path().is_ident("econf") {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename") {
+                    rename = Some(meta.value()?.parse()?);
+                    Ok(())
+                } else {
+                    Err(meta.error("unsupported econf attribute on a variant"))
+                }
+            })?;
+        }
+    }
+    Ok(rename)
+}
+
+/// The name of a named field, with `strip_prefix` of the container removed and `rename_all` applied.
+fn field_name(f: &Field, container: &ContainerAttrs) -> syn::Result<String> {
+    let name = f.ident.as_ref().expect("named field").to_string();
+    let name = match &container.strip_prefix {
+        Some(prefix) => match name.strip_prefix(prefix.as_str()) {
+            Some("") => {
+                return Err(syn::Error::new_spanned(
+                    f,
+                    "field name is empty after `strip_prefix`",
+                ))
+            }
+            Some(stripped) => stripped.to_owned(),
+            None => name,
+        },
+        None => name,
+    };
+    Ok(match container.rename_all {
+        Some(rule) => rule.apply(&name),
+        None => name,
+    })
+}
+
+/// The generated code of `LoadEnv::load` and `LoadEnv::visit`.
+///
+/// `visit` is `None` if the default implementation is used.
+struct Content {
+    load: TokenStream2,
+    visit: Option<TokenStream2>,
+}
+
+/// Wraps the `visit` of a field to record the name of the field in `EnvVar::field`.
+fn field_visit(field: &str, visit: &Option<TokenStream2>) -> Option<TokenStream2> {
+    visit.as_ref().map(|visit| {
+        quote! {
+            loader.enter();
+            loader.field(#field);
+            #visit
+            loader.leave();
+        }
+    })
+}
+
+fn field(
+    f: &Field,
+    container: &ContainerAttrs,
+    value: TokenStream2,
+    segment: TokenStream2,
+) -> syn::Result<Content> {
+    let attrs = field_attrs(f, container)?;
+
+    let segment = match (&attrs.rename, &attrs.prefix) {
+        (Some(overwritten_name), _) => quote!(#overwritten_name),
+        (None, Some(prefix)) => quote!(#prefix),
+        (None, None) => segment,
+    };
+    let name = match attrs.prefixes.first() {
+        Some(prefix) => quote!(&loader.join(#prefix, #segment)),
+        None if attrs.absolute => segment,
+        None if attrs.flatten => quote!(path),
+        None => quote!(&loader.join(path, #segment)),
+    };
+
+    if attrs.skip {
+        // A flattened field has no name of its own.
+        let load = if attrs.flatten {
+            value
+        } else {
+            let verbatim = attrs.verbatim.then(|| quote!(loader.verbatim();));
+            quote! {
+                {
+                    loader.enter();
+                    #verbatim
+                    loader.note_skipped(#name);
+                    loader.leave();
+                    #value
+                }
+            }
+        };
+        return Ok(Content { load, visit: None });
+    }
+
+    // `load` is called in `Loader::load_field` which passes the name as `name`.
+    let ty = &f.ty;
+    let (load, visit) = if attrs.nonzero {
+        let parse = if attrs.duration_float_secs {
+            quote!(::econf::parse::duration_float_secs)
+        } else {
+            quote!(::econf::parse::duration)
+        };
+        (
+            quote!(loader.load_and_validate(#value, name, #parse, |d: &::std::time::Duration| {
+                if d.is_zero() {
+                    Err("must not be zero".to_owned())
+                } else {
+                    Ok(())
+                }
+            })),
+            quote!(loader.visit(#name)),
+        )
+    } else if attrs.duration_float_secs {
+        (
+            quote!(loader.load_and_map(#value, name, ::econf::parse::duration_float_secs)),
+            quote!(loader.visit(#name)),
+        )
+    } else if attrs.serde_duration || attrs.yaml {
+        (
+            quote!(loader.load_from_yaml(#value, name)),
+            quote!(loader.visit(#name)),
+        )
+    } else if attrs.bytesize {
+        (
+            quote!(loader.load_and_map(#value, name, ::econf::parse::bytesize)),
+            quote!(loader.visit(#name)),
+        )
+    } else if attrs.human_number {
+        (
+            quote!(loader.load_and_map(#value, name, ::econf::parse::number)),
+            quote!(loader.visit(#name)),
+        )
+    } else if let Some(strict) = attrs.expand_vars {
+        (
+            quote!(loader.load_expanded(#value, name, #strict)),
+            quote!(loader.visit(#name)),
+        )
+    } else if attrs.ipv6_scope {
+        (
+            quote!(loader.load_and_map(#value, name, ::econf::parse::ipv6_scope)),
+            quote!(loader.visit(#name)),
+        )
+    } else if attrs.bind_alias {
+        (
+            quote!(loader.load_and_map(#value, name, ::econf::parse::bind_alias)),
+            quote!(loader.visit(#name)),
+        )
+    } else if let (Some(delimiter), Some(kv_sep)) = (&attrs.delimiter, &attrs.kv_sep) {
+        (
+            quote!(loader.load_and_map(#value, name, |s| ::econf::parse::delimited_pairs(s, #delimiter, #kv_sep))),
+            quote!(loader.visit(#name)),
+        )
+    } else if let (Some(delimiter), Some(_)) = (&attrs.delimiter, attrs.humantime) {
+        (
+            quote!(loader.load_and_map(#value, name, |s| ::econf::parse::delimited_durations(s, #delimiter))),
+            quote!(loader.visit(#name)),
+        )
+    } else if let Some(delimiter) = &attrs.delimiter {
+        (
+            quote!(loader.load_and_map(#value, name, |s| ::econf::parse::delimited(s, #delimiter))),
+            quote!(loader.visit(#name)),
+        )
+    } else if let Some(parse_with) = &attrs.parse_with {
+        (
+            quote!(loader.load_and_map(#value, name, #parse_with)),
+            quote!(loader.visit(#name)),
+        )
+    } else if attrs.shell_words {
+        (
+            quote!(loader.load_and_map(#value, name, ::econf::parse::shell_words)),
+            quote!(loader.visit(#name)),
+        )
+    } else if attrs.from_str {
+        (
+            quote!(loader.load_and_map(#value, name, ::econf::parse::optional)),
+            quote!(loader.visit(#name)),
+        )
+    } else if attrs.keyed {
+        // Keys are only known at runtime, so nothing is visited.
+        (quote!(loader.load_keyed(#value, name)), quote!())
+    } else if let (true, Some(_)) = (attrs.indexed, attrs.sparse) {
+        // Same as `keyed`, the length is only known at runtime.
+        (quote!(loader.load_sparse(#value, name)), quote!())
+    } else if attrs.indexed {
+        // Same as `keyed`, the length is only known at runtime.
+        (quote!(loader.load_indexed(#value, name)), quote!())
+    } else if attrs.ci_keys {
+        // Same as `keyed`, keys are only known at runtime.
+        (quote!(loader.load_ci_keys(#value, name)), quote!())
+    } else if attrs.nested_keys {
+        let sep = attrs.sep.as_ref().map_or_else(|| "_".into(), LitStr::value);
+        (
+            quote!(loader.load_nested_keys(#value, name, #sep)),
+            quote!(),
+        )
+    } else {
+        (
+            quote!(#value.load(name, loader)),
+            quote!(<#ty as ::econf::LoadEnv>::visit(#name, loader)),
+        )
+    };
+    let load = quote!(loader.load_field(#name, |loader, name| #load));
+    let load = if attrs.required {
+        quote!(loader.load_required(#name, |loader| #load))
+    } else {
+        load
+    };
+    let load = if attrs.warn_if_default {
+        quote! {{
+            let value = #load;
+            if value == <#ty as ::std::default::Default>::default() {
+                loader.note_default(#name);
+            }
+            value
+        }}
+    } else {
+        load
+    };
+
+    let mut scope = vec![];
+    if attrs.verbatim {
+        scope.push(quote!(loader.verbatim();));
+    }
+    if !attrs.prefixes.is_empty() {
+        let prefixes = &attrs.prefixes;
+        scope.push(quote!(loader.set_prefixes(&[#(#prefixes),*]);));
+    }
+    if attrs.secret {
+        scope.push(quote!(loader.secret();));
+    }
+    if attrs.skip_if_empty {
+        scope.push(quote!(loader.skip_if_empty();));
+    }
+    if let Some(example) = &attrs.example {
+        scope.push(quote!(loader.example(#example);));
+    }
+    if let Some(default) = &attrs.default {
+        // Whether the type is a struct is only known by its `LoadEnv` impl, so this is checked on compiling `load`.
+        if attrs.loads_by_trait() {
+            scope.push(quote_spanned! {default.span()=>
+                const {
+                    assert!(
+                        !<#ty as ::econf::LoadEnv>::SUBTREE,
+                        "`default` cannot be used on a struct deriving `LoadEnv`"
+                    )
+                };
+            });
+        }
+        scope.push(quote!(loader.default_value(#name, #default);));
+    }
+
+    if scope.is_empty() {
+        return Ok(Content {
+            load,
+            visit: Some(quote!(#visit;)),
+        });
+    }
+
+    Ok(Content {
+        load: quote! {
+            {
+                loader.enter();
+                #(#scope)*
+                let value = #load;
+                loader.leave();
+                value
+            }
+        },
+        visit: Some(quote! {
+            loader.enter();
+            #(#scope)*
+            #visit;
+            loader.leave();
+        }),
+    })
+}
+
+fn content(name: &Ident, data: &Data, container: &ContainerAttrs) -> syn::Result<Content> {
+    if container.by_index_or_name && !matches!(data, Data::Enum(_)) {
+        return Err(syn::Error::new_spanned(
+            name,
+            "`by_index_or_name` can only be used on enums",
+        ));
+    }
+
+    if container.ascii_case_insensitive && !matches!(data, Data::Enum(_)) {
+        return Err(syn::Error::new_spanned(
+            name,
+            "`ascii_case_insensitive` can only be used on enums",
+        ));
+    }
+
+    let content = match data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => {
+                let fields = fields
+                    .named
+                    .iter()
+                    .map(|f| {
+                        let ident = &f.ident;
+                        let field_name = field_name(f, container)?;
+                        let content =
+                            field(f, container, quote!(self.#ident), quote!(#field_name))?;
+                        Ok((ident, content))
+                    })
+                    .collect::<syn::Result<Vec<_>>>()?;
+                let loads = fields.iter().map(|(ident, content)| {
+                    let load = &content.load;
+                    quote!(#ident: #load,)
                 });
-                quote! {
-                    Self { #(
-                        #fields
-                    )* }
+                let visits = fields.iter().map(|(ident, content)| {
+                    let ident = ident.as_ref().expect("named field").to_string();
+                    field_visit(ident.trim_start_matches("r#"), &content.visit)
+                });
+                Content {
+                    load: quote! {
+                        Self { #(
+                            #loads
+                        )* }
+                    },
+                    visit: Some(quote!(#(#visits)*)),
                 }
             }
             Fields::Unnamed(fields) => {
-                let fields = fields.unnamed.iter().enumerate().map(|(i, f)| {
-                    let i = syn::Index::from(i);
-                    let i = &i;
-                    if is_skip(f) {
-                        return quote! { self.#i, };
-                    }
-                    match find_renaming(f) {
-                        Some(overwritten_name) => quote! {
-                            self.#i.load(&(path.to_owned() + "_" + #overwritten_name), loader),
-                        },
-                        None => quote! {
-                            self.#i.load(&(path.to_owned() + "_" + &#i.to_string()), loader),
-                        },
-                    }
-                });
-                quote! {
-                    Self ( #(
-                        #fields
-                    )* )
+                let fields = fields
+                    .unnamed
+                    .iter()
+                    .enumerate()
+                    .map(|(i, f)| {
+                        let i = syn::Index::from(i);
+                        field(f, container, quote!(self.#i), quote!(&#i.to_string()))
+                    })
+                    .collect::<syn::Result<Vec<_>>>()?;
+                let loads = fields.iter().map(|content| &content.load);
+                let visits = fields
+                    .iter()
+                    .enumerate()
+                    .map(|(i, content)| field_visit(&i.to_string(), &content.visit));
+                Content {
+                    load: quote! {
+                        Self ( #(
+                            #loads,
+                        )* )
+                    },
+                    visit: Some(quote!(#(#visits)*)),
                 }
             }
-            Fields::Unit => quote!(#name),
+            Fields::Unit => Content {
+                load: quote!(#name),
+                visit: Some(quote!()),
+            },
         },
         Data::Enum(data) => {
-            data.variants.iter().for_each(|f| match f.fields {
-                Fields::Named(_) => panic!("Enum variant with named fields are not supported"),
-                Fields::Unnamed(_) => panic!("Enum variant with unnamed fields are not supported"),
-                Fields::Unit => {}
-            });
+            // Unit-only enums are parsed by `FromStr`. Enums with data are deserialized from YAML.
+            let unit_only = data
+                .variants
+                .iter()
+                .all(|v| matches!(v.fields, Fields::Unit));
+            if container.ascii_case_insensitive && !unit_only {
+                return Err(syn::Error::new_spanned(
+                    name,
+                    "`ascii_case_insensitive` requires an enum with unit variants only",
+                ));
+            }
 
-            quote! {
-                loader.load_from_str(self, path)
+            let renames = data
+                .variants
+                .iter()
+                .map(variant_rename)
+                .collect::<syn::Result<Vec<_>>>()?;
+            let renamed = renames.iter().any(Option::is_some);
+            if renamed && !unit_only {
+                return Err(syn::Error::new_spanned(
+                    name,
+                    "`rename` on variants requires an enum with unit variants only",
+                ));
+            }
+
+            // Variant names are matched by the derive if `ascii_case_insensitive` or renamed, or parsed by `FromStr`.
+            let by_name = if container.ascii_case_insensitive || renamed {
+                let variants = data.variants.iter().map(|v| &v.ident);
+                let names: Vec<_> = data
+                    .variants
+                    .iter()
+                    .zip(&renames)
+                    .map(|(v, rename)| {
+                        rename
+                            .as_ref()
+                            .map_or_else(|| v.ident.to_string(), LitStr::value)
+                    })
+                    .collect();
+                let expected = names.join(", ");
+                let matches = if container.ascii_case_insensitive {
+                    names
+                        .iter()
+                        .map(|name| quote!(s.eq_ignore_ascii_case(#name)))
+                        .collect::<Vec<_>>()
+                } else {
+                    names.iter().map(|name| quote!(s == #name)).collect()
+                };
+                quote! {{
+                    let s = s.trim();
+                    #(
+                        if #matches {
+                            return Ok(Self::#variants);
+                        }
+                    )*
+                    Err(format!("unknown variant \"{}\", expected one of {}", s, #expected))
+                }}
+            } else {
+                quote!(<Self as ::std::str::FromStr>::from_str(s).map_err(|e| e.to_string()))
+            };
+
+            let load = if container.by_index_or_name {
+                if !unit_only {
+                    return Err(syn::Error::new_spanned(
+                        name,
+                        "`by_index_or_name` requires an enum with unit variants only",
+                    ));
+                }
+                let indices = 0..data.variants.len();
+                let variants = data.variants.iter().map(|v| &v.ident);
+                let len = data.variants.len();
+                quote! {
+                    loader.load_and_map(self, path, |s| match s.trim().parse::<usize>() {
+                        Ok(i) => match i {
+                            #(#indices => Ok(Self::#variants),)*
+                            _ => Err(format!("index {} is out of range of {} variants", i, #len)),
+                        },
+                        Err(_) => #by_name,
+                    })
+                }
+            } else if container.ascii_case_insensitive || renamed {
+                quote!(loader.load_and_map(self, path, |s| -> ::std::result::Result<Self, ::std::string::String> #by_name))
+            } else if unit_only {
+                quote!(loader.load_from_str(self, path))
+            } else {
+                quote!(loader.load_and_map(self, path, ::econf::parse::yaml_enum))
+            };
+
+            if container.verbatim {
+                Content {
+                    load: quote! {
+                        loader.enter();
+                        loader.verbatim();
+                        let value = #load;
+                        loader.leave();
+                        value
+                    },
+                    visit: Some(quote! {
+                        loader.enter();
+                        loader.verbatim();
+                        loader.visit(path);
+                        loader.leave();
+                    }),
+                }
+            } else {
+                Content { load, visit: None }
             }
         }
         Data::Union(_) => unimplemented!("Unions are not supported"),
-    }
+    };
+
+    let content = match &container.separator {
+        Some(separator) => {
+            let Content { load, visit } = content;
+            let visit = visit.unwrap_or_else(|| quote!(loader.visit(path);));
+            Content {
+                load: quote! {
+                    loader.enter();
+                    loader.separator(#separator);
+                    let value = { #load };
+                    loader.leave();
+                    value
+                },
+                visit: Some(quote! {
+                    loader.enter();
+                    loader.separator(#separator);
+                    #visit
+                    loader.leave();
+                }),
+            }
+        }
+        None => content,
+    };
+
+    // The path given by the parent is converted as the parent does, then the names of the fields are kept as is.
+    let content = if container.case_preserve {
+        let Content { load, visit } = content;
+        let visit = visit.unwrap_or_else(|| quote!(loader.visit(path);));
+        Content {
+            load: quote! {
+                let path = &loader.case(path);
+                #load
+            },
+            visit: Some(quote! {
+                let path = &loader.case(path);
+                #visit
+            }),
+        }
+    } else {
+        content
+    };
+
+    Ok(content)
 }
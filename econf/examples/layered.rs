@@ -0,0 +1,27 @@
+use econf::LoadEnv;
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize, LoadEnv)]
+struct Database {
+    url: String,
+    pool_size: u32,
+}
+
+#[derive(Debug, Deserialize, LoadEnv)]
+struct Config {
+    host: String,
+    port: u16,
+    database: Database,
+}
+
+fn main() {
+    simple_logger::init().unwrap();
+
+    // The base configuration comes from a file, e.g. deserialized by `serde_yaml` or the `config` crate.
+    let c: Config = serde_yaml::from_str(include_str!("layered.yaml")).unwrap();
+    println!("Loaded from file: {c:?}");
+
+    // Environment variables such as `APP_PORT` or `APP_DATABASE_POOL_SIZE` override the values from the file.
+    let c = econf::load(c, "app");
+    println!("After loading env: {c:?}");
+}
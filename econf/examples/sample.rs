@@ -2,19 +2,14 @@ use econf::LoadEnv;
 use log::*;
 use std::collections::HashMap;
 
-#[derive(Debug, strum::EnumString, LoadEnv)]
+#[derive(Debug, Default, strum::EnumString, LoadEnv)]
 enum X {
+    #[default]
     V1,
     V2,
     V3,
 }
 
-impl Default for X {
-    fn default() -> X {
-        X::V1
-    }
-}
-
 #[derive(Default, Debug, LoadEnv)]
 struct Y {
     value1: isize,
@@ -9,6 +9,7 @@ enum AuthMode {
     BearerToken,
     #[strum(serialize = "oauth", serialize = "OAuth")]
     OAuth,
+    #[allow(clippy::upper_case_acronyms)]
     JWT,
 }
 
@@ -16,6 +17,7 @@ enum AuthMode {
 struct Config {
     auth_mode: AuthMode,
     data: String,
+    #[econf(secret)]
     passwd: String,
 }
 
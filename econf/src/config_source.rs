@@ -0,0 +1,55 @@
+use config::{ConfigError, Map, Source, Value};
+
+/// A [`config::Source`] that collects environment variables under a prefix.
+///
+/// Lets econf-style environment variables take part in a layered [`config::Config`].
+/// Each environment variable named `PREFIX_NAME` is collected as the key `name` (lower-case) with its value as a string,
+/// which is converted by `config` on deserialization.
+/// The variables are read from the source set by [`set_default_source`](crate::set_default_source) on the current thread,
+/// or the process environment.
+///
+/// ```
+/// # use econf::EconfSource;
+/// std::env::set_var("SOURCE_PORT", "8080");
+///
+/// let config = config::Config::builder()
+///     .set_default("port", 80)
+///     .unwrap()
+///     .add_source(EconfSource::new("SOURCE"))
+///     .build()
+///     .unwrap();
+///
+/// assert_eq!(config.get::<u16>("port").unwrap(), 8080);
+/// ```
+///
+#[derive(Debug, Clone)]
+pub struct EconfSource {
+    prefix: String,
+}
+
+impl EconfSource {
+    /// Create the source collecting environment variables that start with `prefix`.
+    pub fn new(prefix: &str) -> Self {
+        Self {
+            prefix: format!("{}_", prefix.trim_end_matches('_').to_uppercase()),
+        }
+    }
+}
+
+impl Source for EconfSource {
+    fn clone_into_box(&self) -> Box<dyn Source + Send + Sync> {
+        Box::new(self.clone())
+    }
+
+    fn collect(&self) -> Result<Map<String, Value>, ConfigError> {
+        let origin = "econf".to_owned();
+        Ok(crate::source::default_source()
+            .vars()
+            .into_iter()
+            .filter_map(|(name, value)| {
+                let key = name.strip_prefix(&self.prefix)?;
+                (!key.is_empty()).then(|| (key.to_lowercase(), Value::new(Some(&origin), value)))
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,80 @@
+use std::fmt::{self, Display};
+
+/// An error that occurred while loading an environment variable.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LoadError {
+    /// The environment variable is not set.
+    NotFound { name: String },
+    /// The environment variable is set but its value cannot be parsed.
+    Parse {
+        name: String,
+        value: String,
+        msg: String,
+    },
+    /// The value of the environment variable is parsed but rejected by validation.
+    Validation { name: String, msg: String },
+    /// The environment variable is loaded to more than one field.
+    Duplicate { name: String },
+    /// The environment variable is under the prefix but not loaded to any field. See [`Loader::deny_unknown`](crate::Loader::deny_unknown).
+    Unknown { name: String },
+}
+
+impl LoadError {
+    /// The name of the environment variable that caused the error.
+    pub fn name(&self) -> &str {
+        match self {
+            LoadError::NotFound { name }
+            | LoadError::Parse { name, .. }
+            | LoadError::Validation { name, .. }
+            | LoadError::Duplicate { name }
+            | LoadError::Unknown { name } => name,
+        }
+    }
+}
+
+impl Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadError::NotFound { name } => write!(f, "{} is not found", name),
+            LoadError::Parse { name, value, msg } => {
+                write!(f, "{}: error on parsing \"{}\": {}", name, value, msg)
+            }
+            LoadError::Validation { name, msg } => write!(f, "{}: invalid value: {}", name, msg),
+            LoadError::Duplicate { name } => write!(f, "{} is ambiguous", name),
+            LoadError::Unknown { name } => write!(f, "{} is unknown", name),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {}
+
+/// The errors that occurred while loading environment variables by [`try_load`](crate::try_load).
+///
+/// Lists each environment variable that failed to load together with the reason.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error {
+    errors: Vec<LoadError>,
+}
+
+impl Error {
+    pub(crate) fn new(errors: Vec<LoadError>) -> Self {
+        Self { errors }
+    }
+
+    /// The errors of each environment variable that failed to load.
+    pub fn errors(&self) -> &[LoadError] {
+        &self.errors
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "failed to load environment variables")?;
+        for e in &self.errors {
+            write!(f, "\n  {}", e)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for Error {}
@@ -58,13 +58,45 @@
 //!
 //! * Boolean: `bool`
 //! * Integer: `isize`, `usize`, `i8`, `i16`,`i32`,`i64`,`i128`, `u8`,`u16`,`u32`,`u64`,`u128`
-//! * String: `char`, `String`
+//! * String: `char`, `String`, `Cow<'static, str>`
+//!     * A `char` must be a single character. A value such as `ab` or an emoji with modifiers fails to load.
+//!     * A `Cow` keeps the original value, e.g. a borrowed static default, unless the environment variable is set.
 //! * Float: `f32`, `f64`
 //! * Network: `IpAddr`,`Ipv4Addr`,`Ipv6Addr`,`SocketAddr`,`SocketAddrV4`,`SocketAddrV6`
+//! * CIDR ranges: `ipnetwork::IpNetwork`, `ipnetwork::Ipv4Network`, `ipnetwork::Ipv6Network` (with the `ipnetwork` feature)
+//!     * Parsed as `10.0.0.0/8` or `fe80::/10`. An address without a prefix length is loaded as a single host.
 //! * Non-zero types: `NonZeroI128`,`NonZeroI16`,`NonZeroI32`,`NonZeroI64`,`NonZeroI8`,`NonZeroIsize`,`NonZeroU128`, `NonZeroU16`,`NonZeroU32`,`NonZeroU64`,`NonZeroU8`, `NonZeroUsize`
-//! * File system: `PathBuf`
-//! * Containers: `Vec`, `HashSet`, `HashMap`, `Option`, `BTreeMap`, `BTreeSet`, `BinaryHeap`, `LinkedList`, `VecDeque`, `tuple`
+//! * Wrapping integers: `Wrapping`, `Saturating`
+//!     * Out-of-range values are wrapped/saturated with a warning, e.g. `300` is loaded to `Saturating<u8>` as `255`.
+//! * File system: `PathBuf`, `OsString`
+//!     * Values that are not valid UTF-8 are loaded as is.
+//! * Time: `Duration`, `SystemTime`
+//!     * `SystemTime` is parsed in RFC 3339 format in UTC such as `2024-06-01T00:00:00Z`.
+//! * Ranges: `Range<T>`, `RangeInclusive<T>`
+//!     * Parsed as `1000..2000` and `1000..=2000` respectively, or in YAML format such as `{ start: 1000, end: 2000 }`.
+//!       A range whose start is greater than its end is an error.
+//! * Markers: `PhantomData<T>`, `PhantomPinned`
+//!     * Nothing is loaded, so a struct carrying a marker derives `LoadEnv` without `#[econf(skip)]`.
+//! * Smart pointers: `Box<T>`, `Rc<T>`, `Arc<T>`, `Box<str>`, `Rc<str>`, `Arc<str>`
+//!     * The inner value is loaded at the same name. A shared `Rc`/`Arc` is cloned first, so the other owners keep the original value.
+//! * Atomics: `AtomicBool`, `AtomicI8`, `AtomicI16`, `AtomicI32`, `AtomicI64`, `AtomicIsize`, `AtomicU8`, `AtomicU16`, `AtomicU32`, `AtomicU64`, `AtomicUsize`
+//!     * Loaded like the inner primitive into a new atomic, on the targets supporting it.
+//!       With `LoadEnv` in scope, read them by e.g. `AtomicBool::load(&flag, Ordering::Relaxed)`, since `flag.load(..)` resolves to `LoadEnv::load`.
+//! * Ordering: `std::cmp::Reverse<T>`
+//!     * The inner value is loaded at the same name, e.g. `Reverse<u32>` from `5`. `BinaryHeap<Reverse<T>>` is loaded as a min-heap from a YAML sequence.
+//! * Date and time: `chrono::DateTime<Utc>`, `chrono::NaiveDate`, `chrono::NaiveDateTime` (with the `chrono` feature)
+//!     * `DateTime<Utc>` is parsed in RFC 3339 format such as `2024-01-02T03:04:05Z`. An offset other than `Z` is converted to UTC.
+//!     * `NaiveDate` is parsed as `2024-01-02`, and `NaiveDateTime` as `2024-01-02T03:04:05` with optional fractional seconds.
+//! * Date and time: `jiff::Timestamp`, `jiff::Span` (with the `jiff` feature)
+//!     * `Timestamp` is parsed in RFC 3339 format such as `2024-01-02T03:04:05Z`, and `Span` in ISO 8601 format such as `PT1h30m` or in the friendly format such as `1h 30m`.
+//! * URL: `url::Url` (with the `url` feature)
+//! * Containers: `Vec`, `HashSet`, `HashMap`, `Option`, `BTreeMap`, `BTreeSet`, `BinaryHeap`, `LinkedList`, `VecDeque`, `tuple`, array
 //!     * Containers are parsed as YAML format. See [the tests](https://github.com/YushiOMOTE/econf/blob/master/econf/tests/basics.rs).
+//!     * Elements of arbitrary shapes can be loaded as `serde_json::Value`, e.g. `HashMap<String, serde_json::Value>` for schemaless settings.
+//! * Clone-on-write strings: `beef::Cow<'static, str>`, `beef::lean::Cow<'static, str>` (with the `beef` feature)
+//!     * Loaded like `std::borrow::Cow`: a found value is owned, otherwise the original value is kept.
+//! * Inline vectors: `tinyvec::TinyVec`, `tinyvec::ArrayVec` (with the `tinyvec` feature)
+//!     * Parsed as YAML sequences like `Vec`. More elements than the capacity of `ArrayVec` fail to load.
 //!
 //! # Enums
 //!
@@ -86,6 +118,94 @@
 //! }
 //! ```
 //!
+//! With `#[econf(by_index_or_name)]` on an enum, an integer selects the variant by its position from zero,
+//! and anything else is parsed by `FromStr`:
+//!
+//! ```
+//! # use econf::LoadEnv;
+//! #[derive(Debug, strum::EnumString, LoadEnv)]
+//! #[econf(by_index_or_name)]
+//! enum Level {
+//!     Low,    // `0` or `Low`
+//!     Medium, // `1` or `Medium`
+//!     High,   // `2` or `High`
+//! }
+//! ```
+//!
+//! With `#[econf(ascii_case_insensitive)]` on an enum with unit variants only, the variant names are matched ignoring ASCII case
+//! without `FromStr`. It can be combined with `by_index_or_name`:
+//!
+//! ```
+//! # use econf::LoadEnv;
+//! #[derive(Debug, LoadEnv)]
+//! #[econf(ascii_case_insensitive)]
+//! enum Mode {
+//!     Fast, // `Fast`, `fast` or `FAST`
+//!     Safe, // `Safe`, `safe` or `SAFE`
+//! }
+//! ```
+//!
+//! Likewise, `#[econf(rename = "...")]` on the variants of such an enum gives the names matched instead of the variant names,
+//! which don't need to be valid identifiers:
+//!
+//! ```
+//! # use econf::LoadEnv;
+//! #[derive(Debug, LoadEnv)]
+//! enum Auth {
+//!     #[econf(rename = "api-key")]
+//!     ApiKey, // `api-key`
+//!     Basic,  // `Basic`
+//! }
+//! ```
+//!
+//! Enums used in containers such as `Option` are parsed as YAML, which requires [Deserialize](https://docs.rs/serde/latest/serde/trait.Deserialize.html).
+//! This includes the keys of maps such as `HashMap` and `BTreeMap`:
+//!
+//! ```
+//! # use econf::LoadEnv;
+//! # use std::collections::HashMap;
+//! #[derive(Debug, PartialEq, Eq, Hash, serde::Deserialize)]
+//! #[serde(rename_all = "lowercase")]
+//! enum Level {
+//!     Low,
+//!     High,
+//! }
+//!
+//! #[derive(LoadEnv)]
+//! struct A {
+//!     limits: HashMap<Level, u32>, // `{ low: 1, high: 2 }` is loaded as `{Level::Low: 1, Level::High: 2}`
+//! }
+//! ```
+//!
+//! With `#[econf(from_str)]`, `Option<T>` is loaded by `FromStr` instead, where a YAML null token such as `null` or `~` is loaded as `None`:
+//!
+//! ```
+//! # use econf::LoadEnv;
+//! # #[derive(Debug, strum::EnumString, LoadEnv)]
+//! # enum AuthMode {
+//! #     ApiKey,
+//! # }
+//! #[derive(LoadEnv)]
+//! struct A {
+//!     #[econf(from_str)]
+//!     auth: Option<AuthMode>, // `ApiKey` is loaded as `Some(AuthMode::ApiKey)`, `null` as `None`
+//! }
+//! ```
+//!
+//! Enums with data are loaded from YAML instead, which requires [Deserialize](https://docs.rs/serde/latest/serde/trait.Deserialize.html):
+//!
+//! ```
+//! use econf::LoadEnv;
+//! use serde::Deserialize;
+//!
+//! #[derive(Debug, Deserialize, LoadEnv)]
+//! enum Backend {
+//!     Local,
+//!     Remote { host: String, port: u16 },
+//! }
+//! // `{ Remote: { host: example.com, port: 9 } }` is loaded as `Backend::Remote { .. }`, `Local` as `Backend::Local`
+//! ```
+//!
 //! # Nesting
 //!
 //! Nested structs are supported.
@@ -122,6 +242,7 @@
 //! * `PREFIX_V2_V2` is loaded to `a.v2.v2`
 //!
 //! Fields in child structs can be specified by chaining the field names with `_` as a separator.
+//! A trailing `_` in the prefix is not repeated, i.e. both `PREFIX` and `PREFIX_` load `a.v1` from `PREFIX_V1`.
 //! However, there're cases that names conflict. For example,
 //!
 //! ```
@@ -151,6 +272,56 @@
 //!
 //! Here `PREFIX_V2_V1` corresponds to both `a.v2_v1` and `a.v2.v1`. In this case, `econf` prints warning through [`log facade`](https://docs.rs/log/latest/log/) and the value is loaded to both `a.v2_v1` and `a.v2.v1`.
 //!
+//! Another separator can be given by `#[econf(separator = "...")]` on a struct to avoid such conflicts.
+//! The separator applies to the fields of the struct, including nested structs:
+//!
+//! ```
+//! # use econf::LoadEnv;
+//! # #[derive(LoadEnv)]
+//! # struct B {
+//! #     v1: usize,
+//! #     v2: usize,
+//! # }
+//! #[derive(LoadEnv)]
+//! #[econf(separator = "__")]
+//! struct A {
+//!     v2_v1: usize, // will be loaded from `PREFIX__V2_V1`
+//!     v2: B,        // `a.v2.v1` will be loaded from `PREFIX__V2__V1`
+//! }
+//! ```
+//!
+//! `#[econf(flatten)]` loads the fields of a child struct under the path of the parent, without the field name:
+//!
+//! ```
+//! # use econf::LoadEnv;
+//! #[derive(LoadEnv)]
+//! struct A {
+//!     #[econf(flatten)]
+//!     tls: Tls,
+//! }
+//!
+//! #[derive(LoadEnv)]
+//! struct Tls {
+//!     cert: String, // will be loaded from `PREFIX_CERT`
+//! }
+//! ```
+//!
+//! # Generics
+//!
+//! For a generic struct, the derive macro requires `LoadEnv` of each field type that contains a type parameter.
+//! `#[econf(bound = "...")]` on the struct replaces these bounds, e.g. when a field is loaded by a helper attribute:
+//!
+//! ```
+//! # use econf::LoadEnv;
+//! # use std::str::FromStr;
+//! #[derive(LoadEnv)]
+//! #[econf(bound = "T: FromStr, T::Err: std::fmt::Display")]
+//! struct A<T> {
+//!     #[econf(delimiter)]
+//!     x: Vec<T>,
+//! }
+//! ```
+//!
 //! # Skipping fields
 //!
 //! Fields that do not implement LoadEnv or simply should not be loaded by econf can be skipped by adding the `#[econf(skip)]` helper attribute:
@@ -165,6 +336,97 @@
 //! }
 //! ```
 //!
+//! A warning is logged if the environment variable of a skipped field such as `PREFIX_Y` is set, since it is likely a mistake.
+//!
+//! # Default values
+//!
+//! `#[econf(default = "...")]` gives the value used if the environment variable is not set,
+//! instead of keeping the original value. The value is parsed in the same way as the environment variable:
+//!
+//! ```
+//! # use econf::LoadEnv;
+//! #[derive(Default, LoadEnv)]
+//! struct A {
+//!     #[econf(default = "8080")]
+//!     port: u16, // `8080` unless `PREFIX_PORT` is set
+//!     #[econf(default = "[a, b]")]
+//!     hosts: Vec<String>,
+//! }
+//!
+//! let a = econf::load(A::default(), "PREFIX");
+//! ```
+//!
+//! `#[econf(default_true)]` is a shorthand of `#[econf(default = "true")]` for flags that are enabled
+//! unless the environment variable explicitly sets them to `false`:
+//!
+//! ```
+//! # use econf::LoadEnv;
+//! #[derive(Default, LoadEnv)]
+//! struct A {
+//!     #[econf(default_true)]
+//!     cache: bool, // `true` unless `PREFIX_CACHE` is set
+//! }
+//! ```
+//!
+//! `default` cannot be used together with `skip`:
+//!
+//! ```compile_fail
+//! use econf::LoadEnv;
+//!
+//! #[derive(LoadEnv)]
+//! struct A {
+//!     #[econf(skip, default = "8080")]
+//!     port: u16,
+//! }
+//! ```
+//!
+//! Nor on a field of a struct deriving `LoadEnv`, which has no environment variable of its own to default:
+//!
+//! ```compile_fail
+//! use econf::LoadEnv;
+//!
+//! #[derive(Default, LoadEnv)]
+//! struct Server {
+//!     port: u16,
+//! }
+//!
+//! #[derive(Default, LoadEnv)]
+//! struct A {
+//!     #[econf(default = "8080")]
+//!     server: Server,
+//! }
+//!
+//! let a = econf::load(A::default(), "PREFIX");
+//! ```
+//!
+//! `#[econf(warn_if_default)]` logs a warning if a field still has its `Default` value after loading,
+//! which usually means the operator forgot to set it. The type must implement `Default` and `PartialEq`:
+//!
+//! ```
+//! # use econf::LoadEnv;
+//! #[derive(LoadEnv)]
+//! struct A {
+//!     #[econf(warn_if_default)]
+//!     api_key: String, // warns if `PREFIX_API_KEY` is not set or empty
+//! }
+//! ```
+//!
+//! # Ignoring empty values
+//!
+//! An environment variable set to an empty string such as `PREFIX_NAME=` is loaded as an empty value.
+//! With `#[econf(skip_if_empty)]`, it is ignored as if it were not set, keeping the original value:
+//!
+//! ```
+//! # use econf::LoadEnv;
+//! #[derive(LoadEnv)]
+//! struct A {
+//!     #[econf(skip_if_empty)]
+//!     name: String, // `PREFIX_NAME=` keeps the original name
+//!     #[econf(skip_if_empty)]
+//!     title: Option<String>,
+//! }
+//! ```
+//!
 //! # Renaming fields
 //!
 //! Load a field with the given name instead of its Rust's field name. This is helpful if the environment variable name and Rust's field name don't match:
@@ -179,142 +441,1097 @@
 //! }
 //! ```
 //!
-use std::collections::{BTreeMap, BTreeSet, BinaryHeap, HashMap, HashSet, LinkedList, VecDeque};
-use std::hash::Hash;
-use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
-use std::num::{
-    NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroIsize, NonZeroU128,
-    NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU8, NonZeroUsize,
-};
-use std::path::PathBuf;
-
-use serde::de::DeserializeOwned;
-
-pub use econf_derive::LoadEnv;
-
-pub use crate::loader::Loader;
-
-mod loader;
-
-/// Makes the type loadable from environment variables.
-///
-/// [`LoadEnv`](econf_derive::LoadEnv) derive macro automatically implements this trait. Therefore, usually no need to implement this trait manually.
-/// In the example below, the type `A` implements this trait and can be loaded by [`load`](load) method.
-///
-/// ```rust
-/// # use econf::LoadEnv;
-/// #
-/// #[derive(LoadEnv)]
-/// struct A {
-///     x: bool,
-///     y: u64,
-/// }
-/// ```
-///
-/// The trait can be manually implemented.
-///
-/// ```
-/// use econf::{LoadEnv, Loader};
-///
-/// struct A {
-///     x: bool,
-/// }
-///
-/// impl LoadEnv for A {
-///
-///     fn load(self, path: &str, _loader: &mut Loader) -> Self {
-///         match std::env::var(path) {
-///             Ok(s) if s == "POSITIVE" => A { x: true },
-///             Ok(s) if s == "NEGATIVE" => A { x: false },
-///             Ok(_) | Err(_) => self,
-///         }
-///     }
-///
-/// }
-/// ```
-///
-/// `path` is the environment variable name to be loaded.
-/// Return a new value to override the original value (the original value is `self`).
-/// Return `self` to use the original value.
-///
-pub trait LoadEnv
-where
-    Self: Sized,
-{
-    fn load(self, path: &str, loader: &mut Loader) -> Self;
-}
-
-macro_rules! impl_load_env {
-    ($($t:ident),*) => {$(
-        impl LoadEnv for $t {
-            fn load(self, path: &str, loader: &mut Loader) -> Self {
-                loader.load_from_str(self, path)
-            }
-        }
-    )*}
-}
-
-impl_load_env! {
-    bool, char, String,
-    f32, f64,
-    isize, usize,
-    i8, i16, i32, i64, i128,
-    u8, u16, u32, u64, u128,
-    IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6,
-    NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroIsize, NonZeroU128,
-    NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU8, NonZeroUsize, PathBuf
-}
-
-macro_rules! impl_load_env_containers {
-    ($( $t:ident<$( $p:ident : $tb1:ident $(+ $tb2:ident)* ),*> ),*) => {$(
-        impl<$($p),*> LoadEnv for $t<$($p),*>
-        where $( $p : $tb1 $(+ $tb2)* ),*
-        {
-            fn load(self, path: &str, loader: &mut Loader) -> Self {
-                loader.load_from_yaml(self, path)
-            }
-        }
-    )*}
-}
-
-impl_load_env_containers! {
-    Vec<T: DeserializeOwned>,
-    HashSet<T: Eq + Hash + DeserializeOwned>,
-    HashMap<K: Eq + Hash + DeserializeOwned, V: DeserializeOwned>,
-    Option<T: DeserializeOwned>,
-    BTreeMap<K: Ord + DeserializeOwned, V: DeserializeOwned>,
-    BTreeSet<T: Ord + DeserializeOwned>,
-    BinaryHeap<T: Ord + DeserializeOwned>,
-    LinkedList<T: DeserializeOwned>,
-    VecDeque<T: DeserializeOwned>
-}
-
-macro_rules! peel {
-    ($name:ident, $($other:ident,)*) => (impl_load_env_tuples! { $($other,)* })
-}
-
-macro_rules! impl_load_env_tuples {
-    () => ();
-    ( $($name:ident,)+ ) => (
-        impl<$($name),*> LoadEnv for ($($name,)*)
-            where $($name: DeserializeOwned,)*
-        {
-            fn load(self, path: &str, loader: &mut Loader) -> Self {
-                loader.load_from_yaml(self, path)
-            }
-        }
-        peel! { $($name,)* }
-    )
-}
-
-impl_load_env_tuples! { T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, }
-
-/// Load environment variables to a struct.
-///
-/// The member variables in struct `data` will be overridden by environment variables
-/// that start with the prefix `prefix`. In the example below,
-///
-/// * `FOO_X` is loaded to `x`.
+//! The name must not be empty. An empty name is rejected at compile time:
+//!
+//! ```compile_fail
+//! use econf::LoadEnv;
+//!
+//! #[derive(LoadEnv)]
+//! struct A {
+//!     #[econf(rename = "")]
+//!     y: u64,
+//! }
+//! ```
+//!
+//! A common prefix of field names can be removed by `#[econf(strip_prefix = "...")]` on a struct:
+//!
+//! ```
+//! # use econf::LoadEnv;
+//! #[derive(LoadEnv)]
+//! #[econf(strip_prefix = "server_")]
+//! struct A {
+//!     server_host: String, // will be loaded from `PREFIX_HOST`
+//!     server_port: u16,    // will be loaded from `PREFIX_PORT`
+//!     timeout: u64,        // will be loaded from `PREFIX_TIMEOUT`
+//! }
+//! ```
+//!
+//! A naming convention can be applied to all the fields by `#[econf(rename_all = "...")]` on a struct,
+//! one of `lowercase`, `UPPERCASE`, `PascalCase`, `camelCase`, `snake_case`, `SCREAMING_SNAKE_CASE`, `kebab-case`
+//! and `SCREAMING-KEBAB-CASE`. `rename` on a field takes precedence:
+//!
+//! ```
+//! # use econf::LoadEnv;
+//! #[derive(LoadEnv)]
+//! #[econf(rename_all = "kebab-case")]
+//! struct A {
+//!     my_field: String, // will be loaded from `PREFIX_MY-FIELD`
+//!     #[econf(rename = "other")]
+//!     my_other_field: u16, // will be loaded from `PREFIX_OTHER`
+//! }
+//! ```
+//!
+//! Since names are converted to upper-case, conventions differing only in case such as `camelCase` take effect with `verbatim`.
+//!
+//! # Secrets
+//!
+//! The values loaded are logged at info level. `#[econf(secret)]` hides the value of a field from logs and errors:
+//!
+//! ```
+//! # use econf::LoadEnv;
+//! #[derive(LoadEnv)]
+//! struct A {
+//!     user: String,
+//!     #[econf(secret)]
+//!     passwd: String, // logged as `found <redacted>`
+//! }
+//! ```
+//!
+//! # Loading values from files
+//!
+//! If [`LoadOptions::file_fallback`](LoadOptions::file_fallback) is enabled, a value whose environment variable is not set
+//! is read from the file at the path given by the one with the suffix `_FILE`, which is the convention of Docker and Kubernetes secrets.
+//! The contents of the file are trimmed. This also lets containers, which can be too long for an environment variable,
+//! be loaded from YAML files. JSON files can be loaded as well, since YAML is a superset of JSON:
+//!
+//! ```
+//! # use econf::{LoadEnv, LoadOptions};
+//! #[derive(LoadEnv)]
+//! struct A {
+//!     #[econf(secret)]
+//!     password: String, // loaded from the file at `PREFIX_PASSWORD_FILE` unless `PREFIX_PASSWORD` is set
+//!     rules: Vec<String>, // loaded from the file at `PREFIX_RULES_FILE` unless `PREFIX_RULES` is set
+//! }
+//!
+//! let opts = LoadOptions::new().file_fallback(true);
+//! let a = econf::load_with_options(A { password: String::new(), rules: vec![] }, "PREFIX", opts);
+//! ```
+//!
+//! [`Loader::set_format`](Loader::set_format) makes containers, including the files, parsed as strict JSON instead of YAML.
+//!
+//! # Delimited collections
+//!
+//! Containers are parsed as YAML by default. With `#[econf(delimiter)]`, sequences such as `Vec` and `VecDeque`
+//! and sets such as `HashSet` and `BTreeSet` are loaded from a string separated by `,` instead, each element being parsed by `FromStr`.
+//! Whitespace around elements is trimmed, so `a, b, c` is loaded as `a`, `b` and `c`, and an empty string is loaded as an empty collection.
+//! Duplicate elements of sets are merged.
+//! Another delimiter can be given by `#[econf(delimiter = ";")]`:
+//!
+//! ```
+//! # use econf::LoadEnv;
+//! # use std::collections::VecDeque;
+//! #[derive(LoadEnv)]
+//! struct A {
+//!     #[econf(delimiter)]
+//!     x: Vec<u32>, // `1,2,3` is loaded as `vec![1, 2, 3]`
+//!     #[econf(delimiter = ";")]
+//!     y: VecDeque<char>, // `a;b;c` is loaded as `['a', 'b', 'c']`
+//! }
+//! ```
+//!
+//! Since `Duration` doesn't implement `FromStr`, a sequence of durations is loaded by adding `humantime`,
+//! each element being parsed as a `Duration` field:
+//!
+//! ```
+//! # use econf::LoadEnv;
+//! # use std::time::Duration;
+//! #[derive(LoadEnv)]
+//! struct A {
+//!     #[econf(delimiter, humantime)]
+//!     retries: Vec<Duration>, // `1m,30s,1h` is loaded as one minute, 30 seconds and one hour
+//! }
+//! ```
+//!
+//! Maps such as `BTreeMap` and `HashMap` are loaded from key-value pairs by adding `kv_sep`, each key and value being parsed by `FromStr`:
+//!
+//! ```
+//! # use econf::LoadEnv;
+//! # use std::collections::BTreeMap;
+//! #[derive(LoadEnv)]
+//! struct A {
+//!     #[econf(delimiter, kv_sep = "=")]
+//!     x: BTreeMap<String, u32>, // `a=1,b=2` is loaded as `{"a": 1, "b": 2}`
+//! }
+//! ```
+//!
+//! Values can be unit-only enums implementing `FromStr`, e.g. `HashMap<String, AuthMode>` from `a=api-key,b=basic-auth`, avoiding YAML.
+//!
+//! # Shell words
+//!
+//! With the `shlex` feature, `#[econf(shell_words)]` loads a sequence of strings such as `Vec<String>` by splitting
+//! the value by the quoting rules of the shell, instead of YAML:
+//!
+//! ```
+//! # use econf::LoadEnv;
+//! # #[cfg(feature = "shlex")]
+//! #[derive(LoadEnv)]
+//! struct A {
+//!     #[econf(shell_words)]
+//!     args: Vec<String>, // `--foo 'bar baz'` is loaded as `vec!["--foo", "bar baz"]`
+//! }
+//! ```
+//!
+//! # Custom parsers
+//!
+//! `#[econf(parse_with = "path::to::fn")]` loads a field with a function of signature `fn(&str) -> Result<T, E>`
+//! where `E: Display`, instead of the [`LoadEnv`](LoadEnv) implementation of the type. The parsers in [`parse`](parse)
+//! can be used as well:
+//!
+//! ```
+//! # use econf::LoadEnv;
+//! fn words(s: &str) -> Result<Vec<String>, String> {
+//!     Ok(s.split_whitespace().map(String::from).collect())
+//! }
+//!
+//! #[derive(LoadEnv)]
+//! struct A {
+//!     #[econf(parse_with = "words")]
+//!     x: Vec<String>, // `a b c` is loaded as `vec!["a", "b", "c"]`
+//!     #[econf(parse_with = "econf::parse::duration_float_secs")]
+//!     y: std::time::Duration, // `1.5` is loaded as 1.5 seconds
+//! }
+//! ```
+//!
+//! A type implementing only `Deserialize` can be loaded from YAML by `#[econf(yaml)]`, without implementing [`LoadEnv`](LoadEnv).
+//! The field type must be `DeserializeOwned`. Like containers, the value is parsed in the format given by
+//! [`Loader::set_format`](Loader::set_format), and read from the file given by `_FILE` if unset and
+//! [`LoadOptions::file_fallback`](LoadOptions::file_fallback) is enabled:
+//!
+//! ```
+//! # use econf::LoadEnv;
+//! #[derive(serde::Deserialize)]
+//! struct Point {
+//!     x: i32,
+//!     y: i32,
+//! }
+//!
+//! #[derive(LoadEnv)]
+//! struct A {
+//!     #[econf(yaml)]
+//!     origin: Point, // `{x: 1, y: 2}` is loaded as `Point { x: 1, y: 2 }`
+//! }
+//! ```
+//!
+//! # Keyed maps
+//!
+//! Maps are parsed as YAML by default, which replaces the whole map. With `#[econf(keyed)]`, the value of each existing key
+//! of a `HashMap` or `BTreeMap` is loaded field-by-field instead, with the key joined to the name:
+//!
+//! ```
+//! # use econf::LoadEnv;
+//! # use std::collections::HashMap;
+//! #[derive(LoadEnv)]
+//! struct Db {
+//!     host: String,
+//!     port: u16,
+//! }
+//!
+//! #[derive(LoadEnv)]
+//! struct A {
+//!     #[econf(keyed)]
+//!     dbs: HashMap<String, Db>, // `PREFIX_DBS_PRIMARY_HOST` is loaded to `dbs["primary"].host`
+//! }
+//! ```
+//!
+//! Since keys are only known at runtime, the entries of keyed maps are not listed by [`template`](template).
+//!
+//! # Indexed lists
+//!
+//! Likewise, `Vec` is parsed as YAML by default, which replaces the whole list. With `#[econf(indexed)]`, each existing element
+//! is loaded field-by-field instead, with its index joined to the name:
+//!
+//! ```
+//! # use econf::LoadEnv;
+//! #[derive(LoadEnv)]
+//! struct Server {
+//!     host: String,
+//!     port: u16,
+//! }
+//!
+//! #[derive(LoadEnv)]
+//! struct A {
+//!     #[econf(indexed)]
+//!     servers: Vec<Server>, // `PREFIX_SERVERS_1_HOST` is loaded to `servers[1].host`
+//! }
+//! ```
+//!
+//! The length of the list is kept as given, so elements can't be appended this way. As with keyed maps,
+//! the elements are not listed by [`template`](template).
+//!
+//! With `#[econf(indexed, sparse)]`, the list grows up to the highest index set in the environment, so that
+//! `PREFIX_SERVERS_0_*` and `PREFIX_SERVERS_2_*` can be set without `PREFIX_SERVERS_1_*`. The gaps are filled with
+//! the existing elements, or with `Default::default()` past the end of the list:
+//!
+//! ```
+//! # use econf::LoadEnv;
+//! #[derive(Default, LoadEnv)]
+//! struct Server {
+//!     host: String,
+//!     port: u16,
+//! }
+//!
+//! #[derive(LoadEnv)]
+//! struct A {
+//!     #[econf(indexed, sparse)]
+//!     servers: Vec<Server>, // `PREFIX_SERVERS_2_HOST` makes a list of three servers at least
+//! }
+//! ```
+//!
+//! # Case-insensitive map keys
+//!
+//! With `#[econf(ci_keys)]`, a `HashMap<String, V>` collects every environment variable under its name, with the rest of
+//! the name lower-cased as the key. Keys differing only in case are merged into one entry:
+//!
+//! ```
+//! # use econf::LoadEnv;
+//! # use std::collections::HashMap;
+//! #[derive(LoadEnv)]
+//! struct A {
+//!     #[econf(ci_keys)]
+//!     labels: HashMap<String, String>, // `PREFIX_LABELS_TEAM` and `PREFIX_LABELS_Team` are loaded to `labels["team"]`
+//! }
+//! ```
+//!
+//! Each value is parsed in YAML format. Existing entries are kept unless overridden, and their keys are lower-cased as well.
+//!
+//! # Nested map keys
+//!
+//! With `#[econf(nested_keys)]`, a `HashMap<String, serde_yaml::Value>` collects every environment variable under its name
+//! into a nested map, splitting the rest of the name by `_`, or by the separator given by `sep`. Keys are lower-cased:
+//!
+//! ```
+//! # use econf::LoadEnv;
+//! # use std::collections::HashMap;
+//! #[derive(LoadEnv)]
+//! struct A {
+//!     #[econf(nested_keys)]
+//!     cfg: HashMap<String, serde_yaml::Value>, // `PREFIX_CFG_A_B=1` is loaded as `{a: {b: 1}}`
+//!     #[econf(nested_keys, sep = "__")]
+//!     opts: HashMap<String, serde_yaml::Value>, // `PREFIX_OPTS_LOG__MAX_LEVEL=2` is loaded as `{log: {max_level: 2}}`
+//! }
+//! ```
+//!
+//! # Per-field prefixes
+//!
+//! `#[econf(prefixes = ["APP", "GLOBAL"])]` loads a field under the given prefixes instead of the path of the parent.
+//! The prefixes are tried in order, which helps to migrate a single variable to another namespace:
+//!
+//! ```
+//! # use econf::LoadEnv;
+//! #[derive(LoadEnv)]
+//! struct A {
+//!     #[econf(prefixes = ["APP", "GLOBAL"])]
+//!     region: String, // will be loaded from `APP_REGION`, or `GLOBAL_REGION` if not set
+//! }
+//! ```
+//!
+//! # Custom prefixes of nested structs
+//!
+//! `#[econf(prefix = "...")]` loads a nested struct under the given prefix joined to the path of the parent,
+//! in place of the field name. The prefix may consist of multiple segments. With `absolute`, the prefix is used as is,
+//! ignoring the path of the parent:
+//!
+//! ```
+//! # use econf::LoadEnv;
+//! # #[derive(LoadEnv)]
+//! # struct Pool {
+//! #     size: u32,
+//! # }
+//! #[derive(LoadEnv)]
+//! struct A {
+//!     #[econf(prefix = "DB_POOL")]
+//!     pool: Pool, // `pool.size` will be loaded from `PREFIX_DB_POOL_SIZE`
+//!     #[econf(prefix = "SHARED_POOL", absolute)]
+//!     shared: Pool, // `shared.size` will be loaded from `SHARED_POOL_SIZE`
+//! }
+//! ```
+//!
+//! Combined with `prefixes`, the prefix is joined to each of them in place of the field name.
+//! `prefix` cannot be used together with `rename`, which has the same effect on the name, nor with `flatten`, which has no name.
+//!
+//! # Keeping the case of names
+//!
+//! The environment variable names are converted to upper-case by default. `#[econf(verbatim)]` keeps the name as is.
+//! The attribute can be put on a field or on a struct to apply to all of its fields:
+//!
+//! ```
+//! # use econf::LoadEnv;
+//! #[derive(LoadEnv)]
+//! #[allow(non_snake_case)]
+//! struct A {
+//!     x: bool, // will be loaded from `PREFIX_X`
+//!     #[econf(verbatim)]
+//!     mixedCase: u64, // will be loaded from `PREFIX_mixedCase`
+//! }
+//! ```
+//!
+//! Note that the prefix is also used as is in this case.
+//!
+//! `#[econf(case = "preserve")]` on a struct keeps the names of its fields as is, while the path given by the parent
+//! is still converted to upper-case. This suits a nested struct whose fields follow another naming convention:
+//!
+//! ```
+//! # use econf::LoadEnv;
+//! #[derive(LoadEnv)]
+//! #[econf(case = "preserve")]
+//! #[allow(non_snake_case)]
+//! struct Inner {
+//!     mixedCase: u64,
+//! }
+//!
+//! #[derive(LoadEnv)]
+//! struct A {
+//!     x: bool, // will be loaded from `PREFIX_X`
+//!     inner: Inner, // `inner.mixedCase` will be loaded from `PREFIX_INNER_mixedCase`
+//! }
+//! ```
+//!
+//! # Durations
+//!
+//! `Duration` is parsed in [humantime](https://docs.rs/humantime/latest/humantime/fn.parse_duration.html) format such as `1h 30m` or `500ms`.
+//! A bare `0` is loaded as zero.
+//! With `#[econf(duration_float_secs)]`, a bare number is also accepted as fractional seconds:
+//!
+//! ```
+//! # use econf::LoadEnv;
+//! # use std::time::Duration;
+//! #[derive(LoadEnv)]
+//! struct A {
+//!     #[econf(duration_float_secs)]
+//!     timeout: Duration, // `1.5` is loaded as 1.5 seconds, `2m` as 2 minutes
+//! }
+//! ```
+//!
+//! For timeouts and intervals where zero is invalid, `#[econf(nonzero)]` rejects a zero duration such as `0` or `0s`
+//! as a validation error, keeping the original value. It can be combined with `duration_float_secs`:
+//!
+//! ```
+//! # use econf::LoadEnv;
+//! # use std::time::Duration;
+//! #[derive(LoadEnv)]
+//! struct A {
+//!     #[econf(nonzero)]
+//!     interval: Duration,
+//!     #[econf(nonzero, duration_float_secs)]
+//!     timeout: Duration,
+//! }
+//! ```
+//!
+//! Values written by serde, such as `{secs: 60, nanos: 0}`, are loaded by `#[econf(serde_duration)]`.
+//! They are parsed in YAML format, or in the format given by [`Loader::set_format`](Loader::set_format):
+//!
+//! ```
+//! # use econf::LoadEnv;
+//! # use std::time::Duration;
+//! #[derive(LoadEnv)]
+//! struct A {
+//!     #[econf(serde_duration)]
+//!     timeout: Duration, // `{"secs": 60, "nanos": 0}` is loaded as 60 seconds
+//! }
+//! ```
+//!
+//! # Sizes
+//!
+//! With `#[econf(bytesize)]`, integers and `NonZero` integers are loaded from a human-readable size such as `4KiB` or `2 MB`.
+//! See [`parse::bytesize`](parse::bytesize) for the accepted units. Zero is rejected for `NonZero` types:
+//!
+//! ```
+//! # use econf::LoadEnv;
+//! # use std::num::NonZeroUsize;
+//! #[derive(LoadEnv)]
+//! struct A {
+//!     #[econf(bytesize)]
+//!     buffer: NonZeroUsize, // `4KiB` is loaded as 4096
+//! }
+//! ```
+//!
+//! Other integers such as counts can be written with `_` separators and the suffixes `K`, `M`, `G` and `T` by `#[econf(human_number)]`.
+//! See [`parse::number`](parse::number). Without these attributes, integers are parsed by `FromStr` exactly:
+//!
+//! ```
+//! # use econf::LoadEnv;
+//! #[derive(LoadEnv)]
+//! struct A {
+//!     #[econf(human_number)]
+//!     max_requests: u64, // `10_000` and `10k` are loaded as 10000
+//! }
+//! ```
+//!
+//! # Failing on malformed values
+//!
+//! [`load`](load) logs a value that fails to parse and keeps the original value.
+//! [`try_load`](try_load) returns an [`Error`] listing every such variable instead:
+//!
+//! ```
+//! # use econf::LoadEnv;
+//! #[derive(LoadEnv)]
+//! struct A {
+//!     port: u16,
+//! }
+//!
+//! // Fails if `PREFIX_PORT` is set to e.g. `abc`
+//! let a = econf::try_load(A { port: 8080 }, "PREFIX");
+//! ```
+//!
+//! A setting without a sensible default, such as a database URL, can be marked `#[econf(required)]`.
+//! If its environment variable is not set, [`LoadError::NotFound`] is recorded, so [`try_load`](try_load) fails and
+//! [`load`](load) logs an error and keeps the original value. For a nested struct, it is enough that any of its fields is set.
+//! An `Option<T>` field marked `required` must be set as well, where `null` is loaded as `None`:
+//!
+//! ```
+//! # use econf::LoadEnv;
+//! #[derive(LoadEnv)]
+//! struct A {
+//!     #[econf(required)]
+//!     database_url: String,
+//!     #[econf(required)]
+//!     replica_url: Option<String>, // must be set, but may be `null`
+//! }
+//!
+//! // Fails unless both `PREFIX_DATABASE_URL` and `PREFIX_REPLICA_URL` are set
+//! let a = econf::try_load(A { database_url: String::new(), replica_url: None }, "PREFIX");
+//! ```
+//!
+//! `required` cannot be used together with `default` or `skip`.
+//!
+//! Invariants across fields can be checked after loading by `#[econf(validate = "...")]` on the struct,
+//! naming a function that takes the loaded struct and returns `Result<(), String>`. A rejected struct is logged
+//! and makes [`try_load`](try_load) fail, while [`load`](load) returns it as loaded:
+//!
+//! ```
+//! # use econf::LoadEnv;
+//! #[derive(LoadEnv)]
+//! #[econf(validate = "check_pool")]
+//! struct Pool {
+//!     min: u32,
+//!     max: u32,
+//! }
+//!
+//! fn check_pool(pool: &Pool) -> Result<(), String> {
+//!     if pool.min <= pool.max {
+//!         Ok(())
+//!     } else {
+//!         Err(format!("min {} exceeds max {}", pool.min, pool.max))
+//!     }
+//! }
+//!
+//! std::env::set_var("VALIDATE_POOL_MIN", "20");
+//!
+//! let Err(e) = econf::try_load(Pool { min: 1, max: 10 }, "VALIDATE_POOL") else {
+//!     panic!("min exceeds max");
+//! };
+//! assert_eq!(e.errors()[0].to_string(), "VALIDATE_POOL: invalid value: min 20 exceeds max 10");
+//! ```
+//!
+//! Variables under the prefix that match no field, such as `PREFIX_PROT` for `PREFIX_PORT`, are ignored by default.
+//! [`Loader::deny_unknown`](Loader::deny_unknown) reports them as errors from [`Loader::finish`](Loader::finish):
+//!
+//! ```
+//! # use econf::{LoadEnv, Loader};
+//! # #[derive(LoadEnv)]
+//! # struct A {
+//! #     port: u16,
+//! # }
+//! let mut loader = Loader::new();
+//! loader.deny_unknown(true);
+//! let a = A { port: 8080 }.load("PREFIX", &mut loader);
+//!
+//! // Fails if e.g. `PREFIX_PROT` is set
+//! let result = loader.finish("PREFIX");
+//! ```
+//!
+//! # Layering over a config file
+//!
+//! Since [`load`](load) overrides the values it is given, a struct deserialized from a config file by serde
+//! can be loaded as is, so that environment variables take precedence over the file.
+//! The struct needs no `Default`. See [econf/examples/layered.rs](https://github.com/YushiOMOTE/econf/tree/master/econf/examples/layered.rs) for example code.
+//!
+//! ```
+//! # use econf::LoadEnv;
+//! #[derive(serde::Deserialize, LoadEnv)]
+//! struct A {
+//!     host: String,
+//!     port: u16,
+//! }
+//!
+//! std::env::set_var("LAYERED_PORT", "9090");
+//!
+//! let a: A = serde_json::from_str(r#"{"host": "localhost", "port": 8080}"#).unwrap();
+//! let a = econf::load(a, "LAYERED");
+//! assert_eq!(a.host, "localhost");
+//! assert_eq!(a.port, 9090);
+//! ```
+//!
+//! # Integration with `config`
+//!
+//! With the `config-source` feature, `EconfSource` collects environment variables under a prefix
+//! as a [`config::Source`](https://docs.rs/config/latest/config/trait.Source.html), so that they can override other layers of [config](https://docs.rs/config).
+//!
+//! # Expanding variables
+//!
+//! With `#[econf(expand_vars)]`, references to other environment variables such as `$HOME` or `${HOME}` are expanded
+//! before the value is parsed, which is handy for `PathBuf` and `String`. A reference to a missing variable is left as is.
+//! With `#[econf(expand_vars = "strict")]`, it fails the loading instead:
+//!
+//! ```
+//! # use econf::LoadEnv;
+//! # use std::path::PathBuf;
+//! #[derive(LoadEnv)]
+//! struct A {
+//!     #[econf(expand_vars)]
+//!     data: PathBuf, // `$XDG_DATA_HOME/app` is loaded as e.g. `/home/user/.local/share/app`
+//!     #[econf(expand_vars = "strict")]
+//!     cache: PathBuf,
+//! }
+//! ```
+//!
+//! # IPv6 zone ids
+//!
+//! `Ipv6Addr` cannot hold a zone id, so an address such as `fe80::1%eth0` fails to parse.
+//! With `#[econf(ipv6_scope)]`, the zone id is discarded and the rest is loaded:
+//!
+//! ```
+//! # use econf::LoadEnv;
+//! # use std::net::Ipv6Addr;
+//! #[derive(LoadEnv)]
+//! struct A {
+//!     #[econf(ipv6_scope)]
+//!     addr: Ipv6Addr, // `fe80::1%eth0` is loaded as `fe80::1`
+//! }
+//! ```
+//!
+//! Use `SocketAddrV6` instead if the numeric scope id is needed.
+//!
+//! # Bind addresses
+//!
+//! With `#[econf(bind_alias)]`, an `IpAddr` or `Ipv4Addr` field accepts `*` meaning all interfaces, loaded as `0.0.0.0`:
+//!
+//! ```
+//! # use econf::LoadEnv;
+//! # use std::net::IpAddr;
+//! #[derive(LoadEnv)]
+//! struct A {
+//!     #[econf(bind_alias)]
+//!     bind: IpAddr, // `*` is loaded as `0.0.0.0`
+//! }
+//! ```
+//!
+//! # Other sources
+//!
+//! Environment variables are read from the process environment by default.
+//! [`Loader::with_source`](Loader::with_source) reads them from another [`EnvSource`](EnvSource) such as a `HashMap`,
+//! e.g. parsed from a `.env` file, or a fixed set of variables in tests:
+//!
+//! ```
+//! # use econf::{LoadEnv, Loader};
+//! # use std::collections::HashMap;
+//! #[derive(LoadEnv)]
+//! struct A {
+//!     port: u16,
+//! }
+//!
+//! let mut loader = Loader::with_source(HashMap::from([("PREFIX_PORT".to_owned(), "80".to_owned())]));
+//! let a = A { port: 8080 }.load("PREFIX", &mut loader);
+//! assert_eq!(a.port, 80);
+//! ```
+//!
+//! [`ArgsSource`](ArgsSource) reads them from command-line arguments such as `--set PREFIX_PORT=80` instead.
+//!
+//! In tests, [`set_default_source`](set_default_source) replaces the process environment for [`load`](load) and the other
+//! functions on the current thread, without creating a [`Loader`](Loader).
+//!
+//! # Disabling the environment
+//!
+//! For sandboxed or reproducible builds, the `disabled` feature compiles out all the reads of the
+//! process environment. The environment looks empty, so every field keeps its original value or the value given by
+//! `#[econf(default = "...")]`, while names are still tracked and ambiguous names are still reported.
+//! This is decided at compile time, not at runtime. Sources given explicitly, e.g. by [`Loader::with_source`](Loader::with_source), are still read.
+//!
+//! Unlike the other features, `disabled` changes behavior instead of adding to it, so it should only be enabled
+//! by the final binary, not by a library, and is not meant to be turned on by `--all-features`.
+//!
+//! # Templates
+//!
+//! [`template`](template) lists the environment variables of a struct in `.env` format without reading the environment.
+//! A suggested value can be given by `#[econf(example = "...")]`:
+//!
+//! ```
+//! # use econf::LoadEnv;
+//! #[derive(LoadEnv)]
+//! struct A {
+//!     host: String,
+//!     #[econf(example = "8080")]
+//!     port: u16,
+//! }
+//!
+//! // # PREFIX_HOST=
+//! // # PREFIX_PORT=8080
+//! print!("{}", econf::template::<A>("PREFIX"));
+//! ```
+//!
+//! The derive also generates `env_names` on structs, listing the same environment variables paired with the paths of their fields,
+//! e.g. to build a help screen. [`Loader::env_names`](Loader::env_names) does the same with the options of a loader, such as another separator:
+//!
+//! ```
+//! # use econf::LoadEnv;
+//! # #[derive(LoadEnv)]
+//! # struct A {
+//! #     host: String,
+//! #     port: u16,
+//! # }
+//! for (field, name) in A::env_names("PREFIX") {
+//!     println!("{:<8} {}", field, name); // `host     PREFIX_HOST`
+//! }
+//! ```
+//!
+use std::borrow::Cow;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet, BinaryHeap, HashMap, HashSet, LinkedList, VecDeque};
+use std::convert::Infallible;
+use std::ffi::OsString;
+use std::fmt::Display;
+use std::hash::Hash;
+use std::marker::{PhantomData, PhantomPinned};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
+use std::num::{
+    NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroIsize, NonZeroU128,
+    NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU8, NonZeroUsize, ParseIntError, Saturating,
+    Wrapping,
+};
+use std::ops::{Range, RangeInclusive};
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::str::FromStr;
+use std::sync::atomic::{
+    AtomicBool, AtomicI16, AtomicI32, AtomicI64, AtomicI8, AtomicIsize, AtomicU16, AtomicU32,
+    AtomicU64, AtomicU8, AtomicUsize,
+};
+use std::sync::Arc;
+
+#[cfg(feature = "ipnetwork")]
+use ipnetwork::{IpNetwork, Ipv4Network, Ipv6Network};
+
+use log::warn;
+use serde::de::DeserializeOwned;
+
+pub use econf_derive::LoadEnv;
+
+#[cfg(feature = "config-source")]
+pub use crate::config_source::EconfSource;
+pub use crate::error::{Error, LoadError};
+pub use crate::loader::{EnvVar, FieldHook, Format, Loader};
+pub use crate::options::{Builder, LoadOptions};
+pub use crate::report::{LoadRecord, LoadStatus, Report};
+pub use crate::source::{reset_default_source, set_default_source, ArgsSource, Env, EnvSource};
+
+#[cfg(feature = "config-source")]
+mod config_source;
+mod error;
+mod loader;
+mod options;
+pub mod parse;
+mod report;
+mod source;
+
+/// Makes the type loadable from environment variables.
+///
+/// [`LoadEnv`](econf_derive::LoadEnv) derive macro automatically implements this trait. Therefore, usually no need to implement this trait manually.
+/// In the example below, the type `A` implements this trait and can be loaded by [`load`](load) method.
+///
+/// ```rust
+/// # use econf::LoadEnv;
+/// #
+/// #[derive(LoadEnv)]
+/// struct A {
+///     x: bool,
+///     y: u64,
+/// }
+/// ```
+///
+/// The trait can be manually implemented.
+///
+/// ```
+/// use econf::{LoadEnv, Loader};
+///
+/// struct A {
+///     x: bool,
+/// }
+///
+/// impl LoadEnv for A {
+///
+///     fn load(self, path: &str, _loader: &mut Loader) -> Self {
+///         match std::env::var(path) {
+///             Ok(s) if s == "POSITIVE" => A { x: true },
+///             Ok(s) if s == "NEGATIVE" => A { x: false },
+///             Ok(_) | Err(_) => self,
+///         }
+///     }
+///
+/// }
+/// ```
+///
+/// `path` is the environment variable name to be loaded.
+/// Return a new value to override the original value (the original value is `self`).
+/// Return `self` to use the original value.
+///
+/// [`visit`](LoadEnv::visit) reports the environment variables that [`load`](LoadEnv::load) would read, without reading them.
+/// The default implementation reports `path` itself, which fits types loaded from a single environment variable.
+///
+pub trait LoadEnv
+where
+    Self: Sized,
+{
+    /// Whether `load` loads the fields of a struct under `path` rather than `path` itself.
+    ///
+    /// Set by the derive for structs, so that `#[econf(default = "...")]` on such a field fails to compile.
+    #[doc(hidden)]
+    const SUBTREE: bool = false;
+
+    fn load(self, path: &str, loader: &mut Loader) -> Self;
+
+    fn visit(path: &str, loader: &mut Loader) {
+        loader.visit(path);
+    }
+}
+
+macro_rules! impl_load_env {
+    ($($t:ident),*) => {$(
+        impl LoadEnv for $t {
+            fn load(self, path: &str, loader: &mut Loader) -> Self {
+                loader.load_from_str(self, path)
+            }
+        }
+    )*}
+}
+
+impl_load_env! {
+    bool, String,
+    f32, f64,
+    isize, usize,
+    i8, i16, i32, i64, i128,
+    u8, u16, u32, u64, u128,
+    IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6,
+    NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroIsize, NonZeroU128,
+    NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU8, NonZeroUsize
+}
+
+impl LoadEnv for char {
+    fn load(self, path: &str, loader: &mut Loader) -> Self {
+        loader.load_and_map(self, path, |s| {
+            let mut chars = s.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => Ok(c),
+                _ => Err(format!(
+                    "expected single character, got {}",
+                    s.chars().count()
+                )),
+            }
+        })
+    }
+}
+
+macro_rules! impl_load_env_atomic {
+    ($($size:literal: $t:ident),*) => {$(
+        #[cfg(target_has_atomic = $size)]
+        impl LoadEnv for $t {
+            fn load(self, path: &str, loader: &mut Loader) -> Self {
+                $t::new(loader.load_from_str(self.into_inner(), path))
+            }
+        }
+    )*}
+}
+
+impl_load_env_atomic! {
+    "8": AtomicBool,
+    "8": AtomicI8,
+    "8": AtomicU8,
+    "16": AtomicI16,
+    "16": AtomicU16,
+    "32": AtomicI32,
+    "32": AtomicU32,
+    "64": AtomicI64,
+    "64": AtomicU64,
+    "ptr": AtomicIsize,
+    "ptr": AtomicUsize
+}
+
+impl LoadEnv for PathBuf {
+    fn load(self, path: &str, loader: &mut Loader) -> Self {
+        loader.load_os(self, path)
+    }
+}
+
+impl LoadEnv for OsString {
+    fn load(self, path: &str, loader: &mut Loader) -> Self {
+        loader.load_os(self, path)
+    }
+}
+
+impl<T> LoadEnv for Range<T>
+where
+    T: FromStr + DeserializeOwned + PartialOrd,
+    T::Err: Display,
+{
+    fn load(self, path: &str, loader: &mut Loader) -> Self {
+        loader.load_and_map(self, path, parse::range)
+    }
+}
+
+impl<T> LoadEnv for RangeInclusive<T>
+where
+    T: FromStr + DeserializeOwned + PartialOrd,
+    T::Err: Display,
+{
+    fn load(self, path: &str, loader: &mut Loader) -> Self {
+        loader.load_and_map(self, path, parse::range_inclusive)
+    }
+}
+
+impl<T> LoadEnv for PhantomData<T>
+where
+    T: ?Sized,
+{
+    fn load(self, _: &str, _: &mut Loader) -> Self {
+        self
+    }
+
+    fn visit(_: &str, _: &mut Loader) {}
+}
+
+impl LoadEnv for PhantomPinned {
+    fn load(self, _: &str, _: &mut Loader) -> Self {
+        self
+    }
+
+    fn visit(_: &str, _: &mut Loader) {}
+}
+
+/// An integer parsed before being fitted into the target type.
+enum Integer {
+    Signed(i128),
+    Unsigned(u128),
+}
+
+impl FromStr for Integer {
+    type Err = ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse()
+            .map(Integer::Signed)
+            .or_else(|_| s.parse().map(Integer::Unsigned))
+    }
+}
+
+/// Loads an integer, fitting an out-of-range value by `fit` and logging the adjustment.
+///
+/// `fit` returns the fitted value and whether the value was in range.
+fn load_integer<T, F>(fallback: T, path: &str, loader: &mut Loader, adjustment: &str, fit: F) -> T
+where
+    T: Display,
+    F: FnOnce(Integer) -> (T, bool),
+{
+    let mut out_of_range = None;
+    let value = loader.load_and_map(fallback, path, |s| {
+        let (v, in_range) = fit(s.parse()?);
+        if !in_range {
+            out_of_range = Some(s.to_owned());
+        }
+        Ok::<_, ParseIntError>(v)
+    });
+
+    if let Some(s) = out_of_range {
+        warn!(
+            "econf: loading {}: {} is out of range, {} to {}",
+            loader.case(path),
+            loader.shown(&s),
+            adjustment,
+            loader.shown(&value.to_string())
+        );
+    }
+
+    value
+}
+
+macro_rules! impl_load_env_out_of_range {
+    ($($t:ident),*) => {$(
+        impl LoadEnv for Wrapping<$t> {
+            fn load(self, path: &str, loader: &mut Loader) -> Self {
+                load_integer(self, path, loader, "wrapped", |v| match v {
+                    Integer::Signed(v) => (Wrapping(v as $t), <$t>::try_from(v).is_ok()),
+                    Integer::Unsigned(v) => (Wrapping(v as $t), <$t>::try_from(v).is_ok()),
+                })
+            }
+        }
+
+        impl LoadEnv for Saturating<$t> {
+            fn load(self, path: &str, loader: &mut Loader) -> Self {
+                load_integer(self, path, loader, "saturated", |v| match v {
+                    Integer::Signed(v) => match <$t>::try_from(v) {
+                        Ok(v) => (Saturating(v), true),
+                        Err(_) if v < 0 => (Saturating(<$t>::MIN), false),
+                        Err(_) => (Saturating(<$t>::MAX), false),
+                    },
+                    Integer::Unsigned(v) => match <$t>::try_from(v) {
+                        Ok(v) => (Saturating(v), true),
+                        Err(_) => (Saturating(<$t>::MAX), false),
+                    },
+                })
+            }
+        }
+    )*}
+}
+
+impl_load_env_out_of_range! {
+    isize, usize,
+    i8, i16, i32, i64, i128,
+    u8, u16, u32, u64, u128
+}
+
+macro_rules! impl_load_env_containers {
+    ($( $t:ident<$( $p:ident : $tb1:ident $(+ $tb2:ident)* ),*> ),*) => {$(
+        impl<$($p),*> LoadEnv for $t<$($p),*>
+        where $( $p : $tb1 $(+ $tb2)* ),*
+        {
+            fn load(self, path: &str, loader: &mut Loader) -> Self {
+                loader.load_from_yaml(self, path)
+            }
+        }
+    )*}
+}
+
+impl_load_env_containers! {
+    Vec<T: DeserializeOwned>,
+    HashSet<T: Eq + Hash + DeserializeOwned>,
+    HashMap<K: Eq + Hash + DeserializeOwned, V: DeserializeOwned>,
+    Option<T: DeserializeOwned>,
+    BTreeMap<K: Ord + DeserializeOwned, V: DeserializeOwned>,
+    BTreeSet<T: Ord + DeserializeOwned>,
+    BinaryHeap<T: Ord + DeserializeOwned>,
+    LinkedList<T: DeserializeOwned>,
+    VecDeque<T: DeserializeOwned>
+}
+
+impl<T, const N: usize> LoadEnv for [T; N]
+where
+    T: DeserializeOwned,
+{
+    fn load(self, path: &str, loader: &mut Loader) -> Self {
+        loader.load_from_yaml_and_map(self, path, |v: Vec<T>| {
+            let len = v.len();
+            v.try_into()
+                .map_err(|_| format!("expected {} elements, found {}", N, len))
+        })
+    }
+}
+
+impl<T> LoadEnv for Box<T>
+where
+    T: LoadEnv,
+{
+    const SUBTREE: bool = T::SUBTREE;
+
+    fn load(self, path: &str, loader: &mut Loader) -> Self {
+        Box::new((*self).load(path, loader))
+    }
+
+    fn visit(path: &str, loader: &mut Loader) {
+        T::visit(path, loader)
+    }
+}
+
+impl<T> LoadEnv for Reverse<T>
+where
+    T: LoadEnv,
+{
+    const SUBTREE: bool = T::SUBTREE;
+
+    fn load(self, path: &str, loader: &mut Loader) -> Self {
+        Reverse(self.0.load(path, loader))
+    }
+
+    fn visit(path: &str, loader: &mut Loader) {
+        T::visit(path, loader)
+    }
+}
+
+macro_rules! impl_load_env_shared {
+    ($($t:ident),*) => {$(
+        impl<T> LoadEnv for $t<T>
+        where
+            T: LoadEnv + Clone,
+        {
+            const SUBTREE: bool = T::SUBTREE;
+
+            fn load(self, path: &str, loader: &mut Loader) -> Self {
+                // The inner value is cloned only if it is shared.
+                $t::new($t::unwrap_or_clone(self).load(path, loader))
+            }
+
+            fn visit(path: &str, loader: &mut Loader) {
+                T::visit(path, loader)
+            }
+        }
+    )*}
+}
+
+impl_load_env_shared! { Rc, Arc }
+
+macro_rules! impl_load_env_str {
+    ($($t:ident),*) => {$(
+        impl LoadEnv for $t<str> {
+            fn load(self, path: &str, loader: &mut Loader) -> Self {
+                loader.load_and_map(self, path, |s| Ok::<_, Infallible>(s.into()))
+            }
+        }
+    )*}
+}
+
+impl_load_env_str! { Box, Rc, Arc }
+
+impl LoadEnv for Cow<'static, str> {
+    fn load(self, path: &str, loader: &mut Loader) -> Self {
+        loader.load_and_map(self, path, |s| {
+            Ok::<_, Infallible>(Cow::Owned(s.to_owned()))
+        })
+    }
+}
+
+macro_rules! peel {
+    ($name:ident, $($other:ident,)*) => (impl_load_env_tuples! { $($other,)* })
+}
+
+macro_rules! impl_load_env_tuples {
+    () => ();
+    ( $($name:ident,)+ ) => (
+        impl<$($name),*> LoadEnv for ($($name,)*)
+            where $($name: DeserializeOwned,)*
+        {
+            fn load(self, path: &str, loader: &mut Loader) -> Self {
+                loader.load_from_yaml(self, path)
+            }
+        }
+        peel! { $($name,)* }
+    )
+}
+
+impl_load_env_tuples! { T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12, T13, T14, T15, }
+
+/// Load environment variables to a struct.
+///
+/// The member variables in struct `data` will be overridden by environment variables
+/// that start with the prefix `prefix`. In the example below,
+///
+/// * `FOO_X` is loaded to `x`.
 /// * `FOO_Y` is loaded to `y`.
 ///
 ///
@@ -340,12 +1557,423 @@ pub fn load<T>(data: T, prefix: &str) -> T
 where
     T: LoadEnv,
 {
+    Builder::new().prefix(prefix).load(data)
+}
+
+/// Load environment variables to a struct in place.
+///
+/// Works like [`load`](load), but overrides the member variables of `data` behind a mutable reference,
+/// e.g. a struct that is a part of a larger object. Since [`LoadEnv::load`] takes the value,
+/// `data` is temporarily replaced by `T::default()` while loading, which requires `T: Default`.
+///
+/// ```rust
+/// # use econf::LoadEnv;
+/// #
+/// #[derive(Debug, Default, LoadEnv)]
+/// struct A {
+///     x: bool,
+///     y: u64,
+/// }
+///
+/// struct App {
+///     config: A,
+/// }
+///
+/// std::env::set_var("MUT_Y", "2");
+///
+/// let mut app = App { config: A { x: true, y: 42 } };
+/// econf::load_mut(&mut app.config, "MUT");
+/// assert!(app.config.x);
+/// assert_eq!(app.config.y, 2);
+/// ```
+///
+pub fn load_mut<T>(data: &mut T, prefix: &str)
+where
+    T: LoadEnv + Default,
+{
+    let mut loader = Loader::new();
+    *data = std::mem::take(data).load(prefix, &mut loader);
+}
+
+/// Load environment variables to a struct, failing on malformed values.
+///
+/// Works like [`load`](load), but instead of keeping the original value of a member variable
+/// whose environment variable fails to parse or validate, returns an [`Error`] listing every such failure.
+/// Ambiguous names are only warned as in [`load`](load).
+///
+/// ```rust
+/// # use econf::LoadEnv;
+/// #
+/// #[derive(Debug, LoadEnv)]
+/// struct A {
+///     x: bool,
+///     y: u64,
+/// }
+///
+/// let a = A {
+///     x: true,
+///     y: 42,
+/// };
+///
+/// std::env::set_var("TRY_Y", "abc");
+///
+/// let e = econf::try_load(a, "TRY").unwrap_err();
+/// assert_eq!(e.errors()[0].name(), "TRY_Y");
+/// ```
+///
+pub fn try_load<T>(data: T, prefix: &str) -> Result<T, Error>
+where
+    T: LoadEnv,
+{
+    let (data, errors) = load_with_errors(data, prefix);
+    if errors.is_empty() {
+        Ok(data)
+    } else {
+        Err(Error::new(errors))
+    }
+}
+
+/// Load environment variables to a struct and report the outcome of each environment variable.
+///
+/// Works like [`load`](load), and returns a [`Report`] telling which environment variables were found and parsed.
+///
+/// ```rust
+/// # use econf::{LoadEnv, LoadStatus};
+/// #
+/// #[derive(Debug, LoadEnv)]
+/// struct A {
+///     x: bool,
+///     y: u64,
+/// }
+///
+/// std::env::set_var("WITH_REPORT_Y", "2");
+///
+/// let (a, report) = econf::load_with_report(A { x: true, y: 42 }, "WITH_REPORT");
+/// assert_eq!(report.records()[0].status, LoadStatus::NotFound);
+/// assert_eq!(report.records()[1].status, LoadStatus::Loaded);
+/// ```
+///
+pub fn load_with_report<T>(data: T, prefix: &str) -> (T, Report)
+where
+    T: LoadEnv,
+{
+    let mut loader = Loader::new();
+    let data = data.load(prefix, &mut loader);
+    (data, loader.report())
+}
+
+/// Load environment variables to a struct and return the loader for inspection.
+///
+/// Works like [`load`](load), and returns the [`Loader`] used, so that the errors including ambiguous names,
+/// the records, the unused variables and so on can be queried afterwards.
+///
+/// ```rust
+/// # use econf::{LoadEnv, LoadError};
+/// #
+/// #[derive(Debug, LoadEnv)]
+/// struct A {
+///     x: bool,
+///     #[econf(rename = "x")]
+///     y: bool,
+/// }
+///
+/// let (a, loader) = econf::load_inspect(A { x: true, y: true }, "INSPECT");
+/// assert_eq!(loader.errors(), [LoadError::Duplicate { name: "INSPECT_X".into() }]);
+/// assert_eq!(loader.records().len(), 2);
+/// ```
+///
+pub fn load_inspect<T>(data: T, prefix: &str) -> (T, Loader)
+where
+    T: LoadEnv,
+{
+    let mut loader = Loader::new();
+    let data = data.load(prefix, &mut loader);
+    (data, loader)
+}
+
+/// Load environment variables to a struct with [`LoadOptions`], failing on malformed values.
+///
+/// Works like [`try_load`](try_load), with the separator, case, source, failure policies and so on given by `opts`.
+/// [`load`](load) is a shortcut for the default options that ignores failures.
+///
+/// ```rust
+/// # use econf::{LoadEnv, LoadError, LoadOptions};
+/// # use std::collections::HashMap;
+/// #
+/// #[derive(Debug, LoadEnv)]
+/// struct A {
+///     x: bool,
+///     y: u64,
+/// }
+///
+/// let source = HashMap::from([
+///     ("opts__x".to_owned(), "false".to_owned()),
+///     ("OPTS__Z".to_owned(), "1".to_owned()),
+/// ]);
+/// let opts = LoadOptions::new()
+///     .source(source)
+///     .separator("__")
+///     .case_insensitive(true)
+///     .deny_unknown(true);
+///
+/// let errors = econf::load_with_options(A { x: true, y: 42 }, "OPTS", opts).unwrap_err();
+/// assert_eq!(errors, [LoadError::Unknown { name: "OPTS__Z".into() }]);
+/// ```
+///
+pub fn load_with_options<T>(data: T, prefix: &str, opts: LoadOptions) -> Result<T, Vec<LoadError>>
+where
+    T: LoadEnv,
+{
+    let mut loader = opts.loader(prefix);
+    let data = data.load(prefix, &mut loader);
+    let result = loader.finish(prefix);
+    let errors = if opts.denies_duplicates() {
+        loader.errors().to_vec()
+    } else {
+        result
+            .err()
+            .map(|e| e.errors().to_vec())
+            .unwrap_or_default()
+    };
+    if errors.is_empty() {
+        Ok(data)
+    } else {
+        Err(errors)
+    }
+}
+
+/// Check that the environment variables for a struct parse, without using the loaded values.
+///
+/// Loads into a throwaway `T::default()` and returns the errors [`try_load`](try_load) would return.
+///
+/// ```rust
+/// # use econf::LoadEnv;
+/// #
+/// #[derive(Default, LoadEnv)]
+/// struct A {
+///     x: bool,
+///     y: u64,
+/// }
+///
+/// std::env::set_var("VALIDATE_Y", "abc");
+///
+/// let errors = econf::validate_env::<A>("VALIDATE").unwrap_err();
+/// assert_eq!(errors[0].name(), "VALIDATE_Y");
+/// ```
+///
+pub fn validate_env<T>(prefix: &str) -> Result<(), Vec<LoadError>>
+where
+    T: LoadEnv + Default,
+{
+    let (_, errors) = load_with_errors(T::default(), prefix);
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// Loads `data` and returns the errors other than ambiguous names.
+fn load_with_errors<T>(data: T, prefix: &str) -> (T, Vec<LoadError>)
+where
+    T: LoadEnv,
+{
+    let mut loader = Loader::new();
+    let data = data.load(prefix, &mut loader);
+    let errors = match loader.finish(prefix) {
+        Ok(()) => vec![],
+        Err(e) => e.errors().to_vec(),
+    };
+    (data, errors)
+}
+
+/// Generate a `.env` template listing the environment variables of a struct.
+///
+/// Each environment variable is listed as a commented-out line with the value given by
+/// `#[econf(example = "...")]`, if any. The environment is not read.
+///
+/// ```rust
+/// # use econf::LoadEnv;
+/// #
+/// #[derive(LoadEnv)]
+/// struct A {
+///     host: String,
+///     #[econf(example = "8080")]
+///     port: u16,
+/// }
+///
+/// assert_eq!(econf::template::<A>("APP"), "# APP_HOST=\n# APP_PORT=8080\n");
+/// ```
+///
+pub fn template<T>(prefix: &str) -> String
+where
+    T: LoadEnv,
+{
+    let mut loader = Loader::new();
+    T::visit(prefix, &mut loader);
+    loader
+        .vars()
+        .iter()
+        .map(|var| {
+            format!(
+                "# {}={}\n",
+                var.name,
+                var.example.as_deref().unwrap_or_default()
+            )
+        })
+        .collect()
+}
+
+/// Compute the name of the environment variable loaded to a field.
+///
+/// `segments` are the names of the fields from the outermost struct to the field, as given to the derive macro.
+/// They are joined to `prefix` with `_` and converted to upper-case, as done by [`load`](load):
+///
+/// ```rust
+/// assert_eq!(econf::env_name("app", &["v2", "v1"]), "APP_V2_V1");
+/// assert_eq!(econf::env_name("APP_", &["port"]), "APP_PORT");
+/// ```
+///
+/// [`Loader::env_name`](Loader::env_name) does the same with the options of a loader, such as another separator.
+///
+pub fn env_name(prefix: &str, segments: &[&str]) -> String {
+    Loader::new().env_name(prefix, segments)
+}
+
+/// Load environment variables to a struct, trying multiple prefixes in order.
+///
+/// Works like [`load`](load), but each member variable is looked up under each of `prefixes` in order
+/// and the first environment variable found is used. In the example below,
+///
+/// * `SERVICE_X` is loaded to `x` if it is set. Otherwise, `SHARED_X` is loaded.
+/// * `SERVICE_Y` is loaded to `y` if it is set. Otherwise, `SHARED_Y` is loaded.
+///
+/// ```rust
+/// # use econf::LoadEnv;
+/// #
+/// #[derive(Debug, LoadEnv)]
+/// struct A {
+///     x: bool,
+///     y: u64,
+/// }
+///
+/// let a = A {
+///     x: true,
+///     y: 42,
+/// };
+///
+/// let a = econf::load_prefixes(a, &["SERVICE", "SHARED"]);
+/// ```
+///
+pub fn load_prefixes<T>(data: T, prefixes: &[&str]) -> T
+where
+    T: LoadEnv,
+{
+    let Some(prefix) = prefixes.first() else {
+        return data;
+    };
+
     let mut loader = Loader::new();
+    loader.set_prefixes(prefixes);
     data.load(prefix, &mut loader)
 }
 
+#[cfg(feature = "url")]
+impl LoadEnv for url::Url {
+    fn load(self, path: &str, loader: &mut Loader) -> Self {
+        loader.load_from_str(self, path)
+    }
+}
+
+#[cfg(feature = "ipnetwork")]
+impl_load_env! { IpNetwork, Ipv4Network, Ipv6Network }
+
+#[cfg(feature = "beef")]
+impl LoadEnv for beef::Cow<'static, str> {
+    fn load(self, path: &str, loader: &mut Loader) -> Self {
+        loader.load_and_map(self, path, |s| {
+            Ok::<_, Infallible>(beef::Cow::owned(s.to_owned()))
+        })
+    }
+}
+
+// `beef::lean::Cow` is the same type as `beef::Cow` on other targets.
+#[cfg(all(feature = "beef", target_pointer_width = "64"))]
+impl LoadEnv for beef::lean::Cow<'static, str> {
+    fn load(self, path: &str, loader: &mut Loader) -> Self {
+        loader.load_and_map(self, path, |s| {
+            Ok::<_, Infallible>(beef::lean::Cow::owned(s.to_owned()))
+        })
+    }
+}
+
+#[cfg(feature = "tinyvec")]
+impl<A> LoadEnv for tinyvec::TinyVec<A>
+where
+    A: tinyvec::Array,
+    A::Item: DeserializeOwned,
+{
+    fn load(self, path: &str, loader: &mut Loader) -> Self {
+        loader.load_from_yaml(self, path)
+    }
+}
+
+#[cfg(feature = "tinyvec")]
+impl<A> LoadEnv for tinyvec::ArrayVec<A>
+where
+    A: tinyvec::Array,
+    A::Item: DeserializeOwned,
+{
+    fn load(self, path: &str, loader: &mut Loader) -> Self {
+        loader.load_from_yaml(self, path)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl LoadEnv for chrono::DateTime<chrono::Utc> {
+    fn load(self, path: &str, loader: &mut Loader) -> Self {
+        loader.load_and_map(self, path, |s| {
+            chrono::DateTime::parse_from_rfc3339(s.trim()).map(|t| t.with_timezone(&chrono::Utc))
+        })
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl LoadEnv for chrono::NaiveDate {
+    fn load(self, path: &str, loader: &mut Loader) -> Self {
+        loader.load_from_str(self, path)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl LoadEnv for chrono::NaiveDateTime {
+    fn load(self, path: &str, loader: &mut Loader) -> Self {
+        loader.load_from_str(self, path)
+    }
+}
+
+#[cfg(feature = "jiff")]
+impl LoadEnv for jiff::Timestamp {
+    fn load(self, path: &str, loader: &mut Loader) -> Self {
+        loader.load_from_str(self, path)
+    }
+}
+
+#[cfg(feature = "jiff")]
+impl LoadEnv for jiff::Span {
+    fn load(self, path: &str, loader: &mut Loader) -> Self {
+        loader.load_from_str(self, path)
+    }
+}
+
+impl LoadEnv for std::time::SystemTime {
+    fn load(self, path: &str, loader: &mut Loader) -> Self {
+        loader.load_and_map(self, path, |s| humantime::parse_rfc3339(s.trim()))
+    }
+}
+
 impl LoadEnv for std::time::Duration {
     fn load(self, path: &str, loader: &mut Loader) -> Self {
-        loader.load_and_map(self, path, humantime::parse_duration)
+        loader.load_and_map(self, path, parse::duration)
     }
 }
@@ -1,99 +1,1545 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::ffi::OsString;
 use std::fmt::Display;
+use std::rc::Rc;
 use std::str::FromStr;
 
-use log::{error, info, warn};
+use log::{error, info, log, warn, Level};
 use serde::de::DeserializeOwned;
+use serde_yaml::{Mapping, Value};
+
+use crate::source::default_source;
+use crate::{EnvSource, Error, LoadEnv, LoadError, LoadRecord, LoadStatus, Report};
 
 /// Responsible for loading/parsing environment variables.
 pub struct Loader {
     names: HashSet<String>,
+    source: Rc<dyn EnvSource>,
+    snapshot: Option<HashMap<String, (String, String)>>,
+    scopes: Vec<Scope>,
+    vars: Vec<EnvVar>,
+    errors: Vec<LoadError>,
+    records: Vec<LoadRecord>,
+    subtrees: Vec<(String, bool)>,
+    applied: usize,
+    on_field: Option<FieldHook>,
+    deny_unknown: bool,
+    format: Format,
+    null_tokens: Vec<String>,
+    verbose: bool,
+    file_fallback: bool,
+}
+
+/// The format of containers loaded by [`Loader::load_from_yaml`].
+///
+/// See [`Loader::set_format`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Format {
+    /// YAML, which accepts JSON as well.
+    #[default]
+    Yaml,
+    /// JSON.
+    Json,
+}
+
+impl Format {
+    fn parse<T>(self, s: &str) -> Result<T, String>
+    where
+        T: DeserializeOwned,
+    {
+        match self {
+            Format::Yaml => serde_yaml::from_str(s).map_err(|e| e.to_string()),
+            Format::Json => serde_json::from_str(s).map_err(|e| e.to_string()),
+        }
+    }
 }
 
-impl Default for Loader {
-    fn default() -> Self {
-        Self::new()
+/// A hook called with the environment variable name of a field and whether the field is overridden.
+///
+/// See [`Loader::on_field`].
+pub type FieldHook = Box<dyn FnMut(&str, bool)>;
+
+/// Options applied to the environment variables loaded in a scope.
+#[derive(Clone, Default)]
+struct Scope {
+    verbatim: bool,
+    secret: bool,
+    example: Option<String>,
+    default: Option<(String, String)>,
+    separator: Option<String>,
+    prefixes: Vec<String>,
+    skip_if_empty: bool,
+    field: String,
+}
+
+/// An environment variable reported by [`LoadEnv::visit`](crate::LoadEnv::visit).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct EnvVar {
+    /// The name of the environment variable.
+    pub name: String,
+    /// The example value given by `#[econf(example = "...")]`.
+    pub example: Option<String>,
+    /// The path of the field in Rust, joined by `.` from the outermost struct, e.g. `db.port`.
+    /// Empty if the environment variable is not visited from a struct.
+    pub field: String,
+}
+
+impl Default for Loader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Loader {
+    /// Create the instance.
+    ///
+    /// Environment variables are read from the process environment, or the source given by
+    /// [`set_default_source`](crate::set_default_source) on the current thread.
+    pub fn new() -> Self {
+        Self::from_source(default_source())
+    }
+
+    /// Create the instance reading environment variables from `source` instead of the process environment.
+    ///
+    /// ```
+    /// # use econf::Loader;
+    /// # use std::collections::HashMap;
+    /// let mut loader = Loader::with_source(HashMap::from([("WITH_SOURCE_X".to_owned(), "2".to_owned())]));
+    ///
+    /// assert_eq!(loader.load_from_str(1, "WITH_SOURCE_X"), 2);
+    /// ```
+    ///
+    pub fn with_source<S>(source: S) -> Self
+    where
+        S: EnvSource + 'static,
+    {
+        Self::from_source(Rc::new(source))
+    }
+
+    pub(crate) fn from_source(source: Rc<dyn EnvSource>) -> Self {
+        Self {
+            source,
+            names: HashSet::new(),
+            snapshot: None,
+            scopes: vec![Scope::default()],
+            vars: vec![],
+            errors: vec![],
+            records: vec![],
+            subtrees: vec![],
+            applied: 0,
+            on_field: None,
+            deny_unknown: false,
+            format: Format::Yaml,
+            null_tokens: vec![],
+            verbose: true,
+            file_fallback: false,
+        }
+    }
+
+    /// Records an environment variable to be loaded, without reading it.
+    ///
+    /// Called by [`LoadEnv::visit`](crate::LoadEnv::visit). The options of the current scope are recorded together.
+    ///
+    /// ```
+    /// # use econf::Loader;
+    /// let mut loader = Loader::new();
+    ///
+    /// loader.visit("APP_HOST");
+    /// loader.enter();
+    /// loader.example("8080");
+    /// loader.visit("APP_PORT");
+    /// loader.leave();
+    ///
+    /// assert_eq!(loader.vars()[0].name, "APP_HOST");
+    /// assert_eq!(loader.vars()[0].example, None);
+    /// assert_eq!(loader.vars()[1].name, "APP_PORT");
+    /// assert_eq!(loader.vars()[1].example.as_deref(), Some("8080"));
+    /// ```
+    ///
+    pub fn visit(&mut self, name: &str) {
+        let var = EnvVar {
+            name: self.case(name),
+            example: self.scope().example.clone(),
+            field: self.scope().field.clone(),
+        };
+        self.vars.push(var);
+    }
+
+    /// Returns the names of the environment variables that `T` would be loaded from, without reading them.
+    ///
+    /// The names are built in the same way as [`load`](crate::load), respecting `#[econf(rename)]` and `#[econf(skip)]`.
+    ///
+    /// ```
+    /// # use econf::{LoadEnv, Loader};
+    /// #[derive(LoadEnv)]
+    /// struct A {
+    ///     v1: usize,
+    ///     v2: B,
+    /// }
+    ///
+    /// #[derive(LoadEnv)]
+    /// struct B {
+    ///     v1: usize,
+    ///     #[econf(skip)]
+    ///     v2: usize,
+    /// }
+    ///
+    /// assert_eq!(Loader::collect_names::<A>("PREFIX"), ["PREFIX_V1", "PREFIX_V2_V1"]);
+    /// ```
+    ///
+    pub fn collect_names<T>(prefix: &str) -> Vec<String>
+    where
+        T: LoadEnv,
+    {
+        let mut loader = Self::new();
+        T::visit(prefix, &mut loader);
+        loader.vars.into_iter().map(|var| var.name).collect()
+    }
+
+    /// Returns the names of the environment variables that `T` would be loaded from, paired with the paths of their fields.
+    ///
+    /// Each path is the names of the fields joined by `.`, such as `db.port`. Unlike [`collect_names`](Self::collect_names),
+    /// the names are built with the options of this loader such as [`separator`](Self::separator).
+    /// The environment is not read. `#[derive(LoadEnv)]` generates `env_names` on structs as a shortcut with the default options.
+    ///
+    /// ```
+    /// # use econf::{LoadEnv, Loader};
+    /// #[derive(LoadEnv)]
+    /// struct A {
+    ///     host: String,
+    ///     db: Db,
+    /// }
+    ///
+    /// #[derive(LoadEnv)]
+    /// struct Db {
+    ///     port: u16,
+    /// }
+    ///
+    /// let mut loader = Loader::new();
+    /// loader.separator("__");
+    /// assert_eq!(
+    ///     loader.env_names::<A>("APP"),
+    ///     [
+    ///         ("host".to_owned(), "APP__HOST".to_owned()),
+    ///         ("db.port".to_owned(), "APP__DB__PORT".to_owned()),
+    ///     ]
+    /// );
+    /// ```
+    ///
+    pub fn env_names<T>(&mut self, prefix: &str) -> Vec<(String, String)>
+    where
+        T: LoadEnv,
+    {
+        let start = self.vars.len();
+        T::visit(prefix, self);
+        self.vars
+            .drain(start..)
+            .map(|var| (var.field, var.name))
+            .collect()
+    }
+
+    /// Computes the name of the environment variable loaded to a field, with the options of this loader
+    /// such as [`separator`](Self::separator).
+    ///
+    /// `segments` are the names of the fields from the outermost struct to the field. See [`env_name`](crate::env_name).
+    ///
+    /// ```
+    /// # use econf::Loader;
+    /// let mut loader = Loader::new();
+    /// loader.separator("__");
+    ///
+    /// assert_eq!(loader.env_name("app", &["db", "port"]), "APP__DB__PORT");
+    /// ```
+    ///
+    pub fn env_name(&self, prefix: &str, segments: &[&str]) -> String {
+        let name = segments
+            .iter()
+            .fold(prefix.to_owned(), |path, segment| self.join(&path, segment));
+        self.case(&name)
+    }
+
+    /// Returns the environment variables recorded by [`visit`](Self::visit).
+    pub fn vars(&self) -> &[EnvVar] {
+        &self.vars
+    }
+
+    /// Appends `field` to the path of the field visited in the current scope.
+    ///
+    /// Called by [`LoadEnv::visit`](crate::LoadEnv::visit) of the derive macro for each field.
+    ///
+    /// ```
+    /// # use econf::Loader;
+    /// let mut loader = Loader::new();
+    ///
+    /// loader.enter();
+    /// loader.field("db");
+    /// loader.field("port");
+    /// loader.visit("APP_DB_PORT");
+    /// loader.leave();
+    /// assert_eq!(loader.vars()[0].field, "db.port");
+    /// ```
+    ///
+    pub fn field(&mut self, field: &str) {
+        let scope = self.scope_mut();
+        if !scope.field.is_empty() {
+            scope.field.push('.');
+        }
+        scope.field.push_str(field);
+    }
+
+    /// Sets the example value of the environment variables visited in the current scope.
+    pub fn example(&mut self, example: &str) {
+        self.scope_mut().example = Some(example.to_owned());
+    }
+
+    /// Sets a hook called for every field loaded.
+    ///
+    /// The hook is called with the environment variable name of the field and whether the field is overridden,
+    /// including the fields left unchanged. A struct field is overridden if any of its fields is overridden.
+    ///
+    /// ```
+    /// # use econf::{LoadEnv, Loader};
+    /// #[derive(LoadEnv)]
+    /// struct A {
+    ///     x: u32,
+    ///     y: u32,
+    /// }
+    ///
+    /// std::env::set_var("ON_FIELD_X", "2");
+    ///
+    /// let mut loader = Loader::new();
+    /// loader.on_field(Box::new(|name, applied| println!("{}: {}", name, applied)));
+    ///
+    /// // ON_FIELD_X: true
+    /// // ON_FIELD_Y: false
+    /// let a = A { x: 1, y: 1 }.load("ON_FIELD", &mut loader);
+    /// ```
+    ///
+    pub fn on_field(&mut self, hook: FieldHook) {
+        self.on_field = Some(hook);
+    }
+
+    /// Loads a field by `load` and calls the hook set by [`on_field`](Self::on_field).
+    ///
+    /// Called by the derive macro for each field. `load` receives the loader and `name`.
+    pub fn load_field<T, F>(&mut self, name: &str, load: F) -> T
+    where
+        F: FnOnce(&mut Self, &str) -> T,
+    {
+        let applied = self.applied;
+        let value = load(self, name);
+        let name = self.case(name);
+        if let Some(hook) = &mut self.on_field {
+            hook(&name, self.applied != applied);
+        }
+        value
+    }
+
+    /// Loads a required field by `load`, recording [`LoadError::NotFound`] if no environment variable is found.
+    ///
+    /// Called by the derive macro for `#[econf(required)]`. The field counts as found if any environment variable
+    /// looked up while loading it is set, even if it fails to parse, so that a malformed value is reported only as a parse error.
+    ///
+    /// ```
+    /// # use econf::{LoadError, Loader};
+    /// let mut loader = Loader::new();
+    ///
+    /// let v = loader.load_required("LOAD_REQUIRED_X", |loader| loader.load_from_str(1, "LOAD_REQUIRED_X"));
+    /// assert_eq!(v, 1);
+    /// assert_eq!(loader.errors(), [LoadError::NotFound { name: "LOAD_REQUIRED_X".into() }]);
+    /// ```
+    ///
+    pub fn load_required<T, F>(&mut self, name: &str, load: F) -> T
+    where
+        F: FnOnce(&mut Self) -> T,
+    {
+        let start = self.records.len();
+        let value = load(self);
+        let found = self.records[start..]
+            .iter()
+            .any(|r| r.status != LoadStatus::NotFound);
+        if !found {
+            let name = self.case(name);
+            error!("econf: loading {}: required but not found", name);
+            self.errors.push(LoadError::NotFound { name });
+        }
+        value
+    }
+
+    /// Loads a struct by `load`, recording whether any environment variable under `path` is loaded.
+    ///
+    /// Called by the derive macro for each struct, so that [`Report::untouched`] can tell
+    /// the nested structs running on their original values only.
+    ///
+    /// ```
+    /// # use econf::Loader;
+    /// let mut loader = Loader::new();
+    ///
+    /// std::env::set_var("LOAD_SUBTREE_A_X", "2");
+    ///
+    /// let a = loader.load_subtree("LOAD_SUBTREE_A", |loader| loader.load_from_str(1, "LOAD_SUBTREE_A_X"));
+    /// let b = loader.load_subtree("LOAD_SUBTREE_B", |loader| loader.load_from_str(1, "LOAD_SUBTREE_B_X"));
+    /// assert_eq!((a, b), (2, 1));
+    /// assert_eq!(loader.report().untouched(), ["LOAD_SUBTREE_B"]);
+    /// ```
+    ///
+    pub fn load_subtree<T, F>(&mut self, path: &str, load: F) -> T
+    where
+        F: FnOnce(&mut Self) -> T,
+    {
+        // Reserve the entry first so that the parent is listed before its children.
+        let index = self.subtrees.len();
+        self.subtrees.push((self.case(path), false));
+        let start = self.records.len();
+        let value = load(self);
+        self.subtrees[index].1 = self.records[start..]
+            .iter()
+            .any(|r| r.status == LoadStatus::Loaded);
+        value
+    }
+
+    fn scope(&self) -> &Scope {
+        self.scopes.last().expect("no scope")
+    }
+
+    fn scope_mut(&mut self) -> &mut Scope {
+        self.scopes.last_mut().expect("no scope")
+    }
+
+    /// Enters a new scope.
+    ///
+    /// The scope inherits the options of the current scope. Options set by
+    /// methods such as [`verbatim`](Self::verbatim) apply until the matching [`leave`](Self::leave).
+    /// The derive macro opens a scope for each field that has options.
+    ///
+    pub fn enter(&mut self) {
+        let scope = self.scope().clone();
+        self.scopes.push(scope);
+    }
+
+    /// Leaves the current scope and restores the options of the outer scope.
+    pub fn leave(&mut self) {
+        if self.scopes.len() > 1 {
+            self.scopes.pop();
+        }
+    }
+
+    /// Sets the prefixes to look up in order.
+    ///
+    /// The first prefix is the one passed to [`load`](crate::LoadEnv::load).
+    /// If an environment variable under the first prefix is not found,
+    /// the same name under the rest of the prefixes is tried in order.
+    /// The prefixes apply to the current scope, so that a field can be given its own prefixes by `#[econf(prefixes = [...])]`.
+    ///
+    /// ```
+    /// # use econf::Loader;
+    /// let mut loader = Loader::new();
+    /// loader.set_prefixes(&["SERVICE", "SHARED"]);
+    ///
+    /// std::env::set_var("SHARED_TIMEOUT", "2");
+    ///
+    /// assert_eq!(loader.load_from_str(1, "SERVICE_TIMEOUT"), 2);
+    /// ```
+    ///
+    pub fn set_prefixes(&mut self, prefixes: &[&str]) {
+        self.scope_mut().prefixes = prefixes
+            .iter()
+            .map(|prefix| prefix.trim_end_matches('_').to_owned())
+            .collect();
+    }
+
+    /// Matches environment variable names ignoring case.
+    ///
+    /// By default, names are matched exactly. If enabled, all the environment variables of the source are read once
+    /// into a snapshot and names are matched against it ignoring case. This costs reading all the environment variables up front,
+    /// and the changes to the environment afterwards are not seen until this is enabled again.
+    ///
+    /// ```
+    /// # use econf::Loader;
+    /// let mut loader = Loader::new();
+    ///
+    /// std::env::set_var("insensitive_port", "2");
+    ///
+    /// assert_eq!(loader.load_from_str(1, "INSENSITIVE_PORT"), 1);
+    /// loader.case_insensitive(true);
+    /// assert_eq!(loader.load_from_str(1, "INSENSITIVE_PORT"), 2);
+    /// ```
+    ///
+    pub fn case_insensitive(&mut self, enabled: bool) {
+        self.snapshot = enabled.then(|| {
+            self.source
+                .vars()
+                .into_iter()
+                .map(|(name, value)| (name.to_lowercase(), (name, value)))
+                .collect()
+        });
+    }
+
+    /// Reports the environment variables under the prefix that are not loaded to any field, on [`finish`](Self::finish).
+    ///
+    /// This catches typos such as `APP_PROT` for `APP_PORT`, which are ignored otherwise.
+    ///
+    /// ```
+    /// # use econf::{LoadEnv, LoadError, Loader};
+    /// #[derive(LoadEnv)]
+    /// struct A {
+    ///     port: u16,
+    /// }
+    ///
+    /// std::env::set_var("DENY_PROT", "9090");
+    ///
+    /// let mut loader = Loader::new();
+    /// loader.deny_unknown(true);
+    /// let a = A { port: 8080 }.load("DENY", &mut loader);
+    ///
+    /// let e = loader.finish("DENY").unwrap_err();
+    /// assert_eq!(e.errors(), [LoadError::Unknown { name: "DENY_PROT".into() }]);
+    /// ```
+    ///
+    pub fn deny_unknown(&mut self, enabled: bool) {
+        self.deny_unknown = enabled;
+    }
+
+    /// Sets the format of containers loaded by [`load_from_yaml`](Self::load_from_yaml).
+    ///
+    /// Containers are parsed as YAML by default. Although YAML accepts JSON, some JSON is read differently,
+    /// e.g. a string with escapes or keys like `y` and `on`. [`Format::Json`] parses JSON strictly instead.
+    ///
+    /// ```
+    /// # use econf::{Format, Loader};
+    /// # use std::collections::HashMap;
+    /// let mut loader = Loader::new();
+    ///
+    /// std::env::set_var("FORMAT_MAP", r#"{"a": 1}"#);
+    /// std::env::set_var("FORMAT_YAML", "{a: 1}");
+    ///
+    /// loader.set_format(Format::Json);
+    /// assert_eq!(loader.load_from_yaml(HashMap::new(), "FORMAT_MAP"), HashMap::from([("a".to_owned(), 1)]));
+    /// assert_eq!(loader.load_from_yaml(HashMap::new(), "FORMAT_YAML"), HashMap::<String, u32>::new());
+    /// ```
+    ///
+    pub fn set_format(&mut self, format: Format) {
+        self.format = format;
+    }
+
+    /// Treats the environment variables set to one of `tokens` as not set, keeping the original values.
+    ///
+    /// This is for environments where unset values can't be expressed, e.g. a template filling in `null` or `~`.
+    ///
+    /// ```
+    /// # use econf::Loader;
+    /// let mut loader = Loader::new();
+    ///
+    /// std::env::set_var("NULL_TOKENS_PORT", "null");
+    ///
+    /// loader.null_tokens(&["null", "~"]);
+    /// assert_eq!(loader.load_from_str("8080".to_owned(), "NULL_TOKENS_PORT"), "8080");
+    /// ```
+    ///
+    pub fn null_tokens(&mut self, tokens: &[&str]) {
+        self.null_tokens = tokens.iter().map(|&token| token.to_owned()).collect();
+    }
+
+    /// Reads the value from the file at the path given by the environment variable with the suffix `_FILE`,
+    /// if the environment variable itself is not set.
+    ///
+    /// This follows the convention of Docker and Kubernetes secrets, e.g. `/run/secrets/pw` given by `PASSWORD_FILE` for `PASSWORD`.
+    /// The contents of the file are trimmed. A file that fails to be read is logged and recorded as an error, keeping the original value.
+    /// Applies to [`load_and_map`](Self::load_and_map) and the functions built on it, including containers loaded by
+    /// [`load_from_yaml`](Self::load_from_yaml).
+    ///
+    /// ```
+    /// # use econf::Loader;
+    /// let path = std::env::temp_dir().join(format!("econf-file-fallback-{}", std::process::id()));
+    /// std::fs::write(&path, "hunter2\n").unwrap();
+    /// std::env::set_var("FILE_FALLBACK_PASSWORD_FILE", &path);
+    ///
+    /// let mut loader = Loader::new();
+    /// loader.file_fallback(true);
+    /// assert_eq!(loader.load_from_str(String::new(), "FILE_FALLBACK_PASSWORD"), "hunter2");
+    /// # std::fs::remove_file(&path).unwrap();
+    /// ```
+    ///
+    pub fn file_fallback(&mut self, enabled: bool) {
+        self.file_fallback = enabled;
+    }
+
+    /// Logs the environment variables not found at info level if `enabled` (the default), or at trace level otherwise.
+    ///
+    /// Turning this off keeps the logs of large configs short, as most fields are usually not overridden.
+    /// The other messages are logged regardless.
+    ///
+    /// ```
+    /// # use econf::Loader;
+    /// let mut loader = Loader::new();
+    ///
+    /// loader.verbose(false);
+    /// assert_eq!(loader.load_from_str(1, "VERBOSE_UNSET"), 1);
+    /// ```
+    ///
+    pub fn verbose(&mut self, enabled: bool) {
+        self.verbose = enabled;
+    }
+
+    /// Looks up names in the current scope as is, without converting them to upper-case.
+    ///
+    /// ```
+    /// # use econf::Loader;
+    /// let mut loader = Loader::new();
+    ///
+    /// std::env::set_var("VERBATIM_mixedCase", "2");
+    ///
+    /// loader.enter();
+    /// loader.verbatim();
+    /// assert_eq!(loader.load_from_str(1, "VERBATIM_mixedCase"), 2);
+    /// loader.leave();
+    /// assert_eq!(loader.load_from_str(1, "VERBATIM_mixedCase"), 1);
+    /// ```
+    ///
+    pub fn verbatim(&mut self) {
+        self.scope_mut().verbatim = true;
+    }
+
+    /// Sets the value used if the environment variable `name` is not set, in the current scope.
+    ///
+    /// The value is parsed in the same way as the environment variable. Used for `#[econf(default = "...")]`.
+    ///
+    /// ```
+    /// # use econf::Loader;
+    /// let mut loader = Loader::new();
+    ///
+    /// loader.enter();
+    /// loader.default_value("DEFAULT_PORT", "8080");
+    /// assert_eq!(loader.load_from_str(0, "DEFAULT_PORT"), 8080);
+    /// loader.leave();
+    /// ```
+    ///
+    pub fn default_value(&mut self, name: &str, value: &str) {
+        let name = self.case(name);
+        self.scope_mut().default = Some((name, value.to_owned()));
+    }
+
+    /// Sets the separator used by [`join`](Self::join) in the current scope.
+    ///
+    /// Used for `#[econf(separator = "...")]`.
+    ///
+    /// ```
+    /// # use econf::Loader;
+    /// let mut loader = Loader::new();
+    ///
+    /// loader.enter();
+    /// loader.separator("__");
+    /// assert_eq!(loader.join("APP_SUB", "X"), "APP_SUB__X");
+    /// loader.leave();
+    /// assert_eq!(loader.join("APP_SUB", "X"), "APP_SUB_X");
+    /// ```
+    ///
+    pub fn separator(&mut self, separator: &str) {
+        self.scope_mut().separator = Some(separator.to_owned());
+    }
+
+    /// Hides the values of the environment variables loaded in the current scope from logs and errors.
+    ///
+    /// `<redacted>` is shown instead of the values. Used for `#[econf(secret)]`.
+    pub fn secret(&mut self) {
+        self.scope_mut().secret = true;
+    }
+
+    /// Ignores the environment variables set to an empty string in the current scope, as if they were not set.
+    ///
+    /// Used for `#[econf(skip_if_empty)]`.
+    ///
+    /// ```
+    /// # use econf::Loader;
+    /// let mut loader = Loader::new();
+    ///
+    /// std::env::set_var("SKIP_IF_EMPTY_NAME", "");
+    ///
+    /// assert_eq!(loader.load_from_str("a".to_owned(), "SKIP_IF_EMPTY_NAME"), "");
+    /// loader.enter();
+    /// loader.skip_if_empty();
+    /// assert_eq!(loader.load_from_str("a".to_owned(), "SKIP_IF_EMPTY_NAME"), "a");
+    /// loader.leave();
+    /// ```
+    ///
+    pub fn skip_if_empty(&mut self) {
+        self.scope_mut().skip_if_empty = true;
+    }
+
+    /// Returns `value` to be shown in logs and errors, or `<redacted>` if [`secret`](Self::secret).
+    pub(crate) fn shown(&self, value: &str) -> String {
+        if self.scope().secret {
+            "<redacted>".into()
+        } else {
+            value.into()
+        }
+    }
+
+    /// Check the name conflict of environment variables being loaded.
+    ///
+    /// ```
+    /// # use econf::Loader;
+    /// let mut loader = Loader::new();
+    ///
+    /// assert!(!loader.is_duplicated("PREFIX_X"));
+    /// assert!(!loader.is_duplicated("PREFIX_Y"));
+    /// assert!(loader.is_duplicated("PREFIX_X"));
+    /// ```
+    ///
+    pub fn is_duplicated(&mut self, name: &str) -> bool {
+        !self.names.insert(name.into())
+    }
+
+    /// Warns that the field loaded from the environment variable `name` has its `Default` value after loading.
+    ///
+    /// Such a field is likely to be forgotten by the operator. Used for `#[econf(warn_if_default)]`.
+    ///
+    /// ```
+    /// # use econf::Loader;
+    /// let loader = Loader::new();
+    ///
+    /// loader.note_default("NOTE_DEFAULT_X"); // logs a warning
+    /// ```
+    ///
+    pub fn note_default(&self, name: &str) {
+        warn!("econf: warning: {} has the default value", self.case(name));
+    }
+
+    /// Validates `value` loaded under `path` as a whole, e.g. checks that a minimum doesn't exceed a maximum.
+    ///
+    /// If `validate` rejects the value, the error is logged and recorded as [`LoadError::Validation`] named `path`.
+    /// Since the original value is already consumed, the loaded value is returned either way.
+    /// Used for `#[econf(validate = "...")]`.
+    ///
+    /// ```
+    /// # use econf::{LoadError, Loader};
+    /// let mut loader = Loader::new();
+    ///
+    /// let range = loader.validate((2, 1), "VALIDATE", |(min, max)| {
+    ///     if min <= max {
+    ///         Ok(())
+    ///     } else {
+    ///         Err("min exceeds max".to_owned())
+    ///     }
+    /// });
+    /// assert_eq!(range, (2, 1));
+    /// assert!(matches!(loader.errors()[0], LoadError::Validation { .. }));
+    /// ```
+    ///
+    pub fn validate<T, V>(&mut self, value: T, path: &str, validate: V) -> T
+    where
+        V: FnOnce(&T) -> Result<(), String>,
+    {
+        if let Err(msg) = validate(&value) {
+            let name = self.case(path);
+            error!("econf: loading {}: invalid value: {}", name, msg);
+            self.errors.push(LoadError::Validation { name, msg });
+        }
+        value
+    }
+
+    /// Warns if the environment variable `name` of a skipped field is set.
+    ///
+    /// Setting the environment variable of a field with `#[econf(skip)]` is likely a mistake, since it is never loaded.
+    /// The field is still skipped. Used for `#[econf(skip)]`.
+    ///
+    /// ```
+    /// # use econf::Loader;
+    /// let loader = Loader::new();
+    ///
+    /// std::env::set_var("NOTE_SKIPPED_X", "2");
+    ///
+    /// loader.note_skipped("NOTE_SKIPPED_X"); // logs a warning
+    /// ```
+    ///
+    pub fn note_skipped(&self, name: &str) {
+        if let Some((name, _)) = self.lookup_env(&self.case(name)) {
+            warn!("econf: warning: {} is set but the field is skipped", name);
+        }
+    }
+
+    /// Joins the path of the parent and the name of a field into an environment variable name.
+    ///
+    /// The two parts are separated by a single separator, `_` unless [`separator`](Self::separator) is set.
+    /// Separators at the boundary are collapsed, so a prefix with a trailing `_` doesn't end up with a double `_` in the name.
+    /// An empty path yields the field name as is.
+    ///
+    /// ```
+    /// # use econf::Loader;
+    /// let loader = Loader::new();
+    ///
+    /// assert_eq!(loader.join("APP_SUB", "X"), "APP_SUB_X");
+    /// assert_eq!(loader.join("APP_", "X"), "APP_X");
+    /// assert_eq!(loader.join("", "X"), "X");
+    /// ```
+    ///
+    pub fn join(&self, path: &str, name: &str) -> String {
+        let separator = self.scope().separator.as_deref().unwrap_or("_");
+        let path = path.trim_end_matches(separator);
+        let name = name.trim_start_matches(separator);
+
+        if name.is_empty() {
+            error!("econf: empty name is joined to {}", path);
+            return path.to_owned();
+        }
+
+        if path.is_empty() {
+            name.to_owned()
+        } else {
+            format!("{}{}{}", path, separator, name)
+        }
+    }
+
+    /// Loads an environment variable and converts it to a specific type.
+    ///
+    /// The function does the following:
+    ///
+    /// * Checks the duplication of environment variable names loaded so far (case insensitive)
+    /// * Loads the environment variable (look up `name` as upper-case unless [`verbatim`](Self::verbatim))
+    /// * Calls `map` function to convert the loaded string to a specific type.
+    ///
+    /// If loading/conversion is successful, the function returns the new value loaded. Otherwise, returns `fallback`.
+    ///
+    /// ```
+    /// # use econf::Loader;
+    /// let mut loader = Loader::new();
+    ///
+    /// std::env::set_var("BAR", "2");
+    /// std::env::set_var("BUZZ", "A");
+    ///
+    /// assert_eq!(loader.load_and_map(1, "FOO", |v| v.parse()), 1);
+    /// assert_eq!(loader.load_and_map(1, "BAR", |v| v.parse()), 2);
+    /// assert_eq!(loader.load_and_map(1, "BUZZ", |v| v.parse()), 1);
+    /// ```
+    ///
+    pub fn load_and_map<T, F, E>(&mut self, fallback: T, name: &str, map: F) -> T
+    where
+        F: FnOnce(&str) -> Result<T, E>,
+        E: Display,
+    {
+        match self.try_load_and_map(name, map) {
+            Ok(v) => v,
+            Err(LoadError::NotFound { .. }) => fallback,
+            Err(e) => {
+                self.errors.push(e);
+                fallback
+            }
+        }
+    }
+
+    /// Loads an environment variable and converts it to a specific type, without showing the value.
+    ///
+    /// Works like [`load_and_map`](Self::load_and_map), but `<redacted>` is logged instead of the value,
+    /// as in the scope of [`secret`](Self::secret).
+    ///
+    /// ```
+    /// # use econf::{LoadError, Loader};
+    /// let mut loader = Loader::new();
+    ///
+    /// std::env::set_var("SECRET_PIN", "abc");
+    ///
+    /// assert_eq!(loader.load_and_map_secret(1234, "SECRET_PIN", |v| v.parse::<u32>()), 1234);
+    /// assert!(matches!(&loader.errors()[0], LoadError::Parse { value, .. } if value == "<redacted>"));
+    /// ```
+    ///
+    pub fn load_and_map_secret<T, F, E>(&mut self, fallback: T, name: &str, map: F) -> T
+    where
+        F: FnOnce(&str) -> Result<T, E>,
+        E: Display,
+    {
+        self.enter();
+        self.secret();
+        let value = self.load_and_map(fallback, name, map);
+        self.leave();
+        value
+    }
+
+    /// Loads an environment variable and converts it to a specific type, returning the error on failure.
+    ///
+    /// Works like [`load_and_map`](Self::load_and_map), but returns [`LoadError::NotFound`] if the environment variable
+    /// is not set and [`LoadError::Parse`] if `map` fails, instead of falling back.
+    /// The returned error is not recorded in [`errors`](Self::errors).
+    ///
+    /// ```
+    /// # use econf::{LoadError, Loader};
+    /// let mut loader = Loader::new();
+    ///
+    /// std::env::set_var("TRY_BAR", "2");
+    /// std::env::set_var("TRY_BUZZ", "A");
+    ///
+    /// assert!(matches!(loader.try_load_and_map("TRY_FOO", |v| v.parse::<u32>()), Err(LoadError::NotFound { .. })));
+    /// assert_eq!(loader.try_load_and_map("TRY_BAR", |v| v.parse::<u32>()), Ok(2));
+    /// assert!(matches!(loader.try_load_and_map("TRY_BUZZ", |v| v.parse::<u32>()), Err(LoadError::Parse { .. })));
+    /// ```
+    ///
+    pub fn try_load_and_map<T, F, E>(&mut self, name: &str, map: F) -> Result<T, LoadError>
+    where
+        F: FnOnce(&str) -> Result<T, E>,
+        E: Display,
+    {
+        let name = self.case(name);
+
+        if self.is_duplicated(&name) {
+            warn!("econf: warning: {} is ambiguous", name);
+            self.errors
+                .push(LoadError::Duplicate { name: name.clone() });
+        }
+
+        let found = match self.lookup(&name) {
+            None if self.file_fallback => {
+                self.lookup_file(&name)?.map(|(name, s)| (name, s, false))
+            }
+            found => found,
+        };
+
+        match found {
+            Some((name, s, default)) => match map(&s) {
+                Ok(v) => {
+                    self.found(&name, &s, default);
+                    Ok(v)
+                }
+                Err(e) => {
+                    // Parsers may echo the input or a part of it in their messages.
+                    let (s, msg) = (self.shown(&s), self.shown(&e.to_string()));
+                    error!(
+                        "econf: loading {}: error on parsing \"{}\": {}",
+                        name, s, msg
+                    );
+                    self.record(&name, LoadStatus::Failed);
+                    Err(LoadError::Parse {
+                        name,
+                        value: s,
+                        msg,
+                    })
+                }
+            },
+            None => {
+                self.not_found(&name);
+                Err(LoadError::NotFound { name })
+            }
+        }
+    }
+
+    /// Loads an environment variable, converts it to a specific type and validates the converted value.
+    ///
+    /// Works like [`load_and_map`](Self::load_and_map). If `validate` rejects the converted value,
+    /// the error is recorded as [`LoadError::Validation`] and `fallback` is returned.
+    ///
+    /// ```
+    /// # use econf::{LoadError, Loader};
+    /// let mut loader = Loader::new();
+    ///
+    /// std::env::set_var("VALIDATE_PORT", "0");
+    ///
+    /// let port = loader.load_and_validate(8080, "VALIDATE_PORT", |v| v.parse::<u16>(), |port| {
+    ///     if *port == 0 {
+    ///         Err("port must not be zero".into())
+    ///     } else {
+    ///         Ok(())
+    ///     }
+    /// });
+    /// assert_eq!(port, 8080);
+    /// assert!(matches!(loader.errors(), [LoadError::Validation { .. }]));
+    /// ```
+    ///
+    pub fn load_and_validate<T, F, E, V>(
+        &mut self,
+        fallback: T,
+        name: &str,
+        map: F,
+        validate: V,
+    ) -> T
+    where
+        F: FnOnce(&str) -> Result<T, E>,
+        E: Display,
+        V: FnOnce(&T) -> Result<(), String>,
+    {
+        let name = self.case(name);
+        match self.try_load_and_map(&name, map) {
+            Ok(v) => match validate(&v) {
+                Ok(()) => v,
+                Err(msg) => {
+                    error!("econf: loading {}: invalid value: {}", name, msg);
+                    self.errors.push(LoadError::Validation { name, msg });
+                    fallback
+                }
+            },
+            Err(LoadError::NotFound { .. }) => fallback,
+            Err(e) => {
+                self.errors.push(e);
+                fallback
+            }
+        }
+    }
+
+    /// Returns the errors that occurred while loading so far.
+    ///
+    /// Parsing errors, validation errors and ambiguous names are recorded.
+    ///
+    /// ```
+    /// # use econf::{LoadError, Loader};
+    /// let mut loader = Loader::new();
+    ///
+    /// std::env::set_var("ERRORS_X", "A");
+    ///
+    /// assert_eq!(loader.load_from_str(1, "ERRORS_X"), 1);
+    /// assert_eq!(loader.load_from_str(1, "ERRORS_Y"), 1);
+    /// assert_eq!(loader.load_from_str(1, "ERRORS_Y"), 1);
+    /// assert!(matches!(loader.errors(), [LoadError::Parse { .. }, LoadError::Duplicate { .. }]));
+    /// ```
+    ///
+    pub fn errors(&self) -> &[LoadError] {
+        &self.errors
     }
-}
 
-impl Loader {
-    /// Create the instance.
-    pub fn new() -> Self {
-        Self {
-            names: HashSet::new(),
+    /// Returns the environment variables looked up so far and whether each was found and parsed.
+    ///
+    /// ```
+    /// # use econf::{LoadRecord, LoadStatus, Loader};
+    /// let mut loader = Loader::new();
+    ///
+    /// std::env::set_var("RECORDS_X", "2");
+    ///
+    /// loader.load_from_str(1, "RECORDS_X");
+    /// loader.load_from_str(1, "RECORDS_Y");
+    /// assert_eq!(loader.records()[0].status, LoadStatus::Loaded);
+    /// assert_eq!(loader.records()[1].status, LoadStatus::NotFound);
+    /// ```
+    ///
+    pub fn records(&self) -> &[LoadRecord] {
+        &self.records
+    }
+
+    /// Completes loading under `prefix`, returning the errors other than ambiguous names.
+    ///
+    /// If [`deny_unknown`](Self::deny_unknown) is enabled, the environment variables under `prefix`
+    /// that were never looked up are recorded as [`LoadError::Unknown`] first.
+    ///
+    /// ```
+    /// # use econf::Loader;
+    /// let mut loader = Loader::new();
+    ///
+    /// std::env::set_var("FINISH_X", "A");
+    ///
+    /// loader.load_from_str(1, "FINISH_X");
+    /// assert_eq!(loader.finish("FINISH").unwrap_err().errors()[0].name(), "FINISH_X");
+    /// ```
+    ///
+    pub fn finish(&mut self, prefix: &str) -> Result<(), Error> {
+        if self.deny_unknown {
+            self.check_unknown(prefix);
+        }
+
+        let errors: Vec<_> = self
+            .errors
+            .iter()
+            .filter(|e| !matches!(e, LoadError::Duplicate { .. }))
+            .cloned()
+            .collect();
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::new(errors))
         }
     }
 
-    /// Check the name conflict of environment variables being loaded.
+    /// Returns the environment variables under `prefix` that were never looked up, sorted by name.
+    ///
+    /// Unlike [`deny_unknown`](Self::deny_unknown), nothing is recorded as an error, so the caller can decide
+    /// what to do with stale entries, e.g. log them as warnings.
+    ///
+    /// ```
+    /// # use econf::{LoadEnv, Loader};
+    /// #[derive(LoadEnv)]
+    /// struct A {
+    ///     port: u16,
+    /// }
+    ///
+    /// std::env::set_var("UNUSED_VARS_PROT", "9090");
+    ///
+    /// let mut loader = Loader::new();
+    /// let a = A { port: 8080 }.load("UNUSED_VARS", &mut loader);
+    /// assert_eq!(loader.unused_vars("UNUSED_VARS"), ["UNUSED_VARS_PROT"]);
+    /// assert!(loader.finish("UNUSED_VARS").is_ok());
+    /// ```
+    ///
+    pub fn unused_vars(&self, prefix: &str) -> Vec<String> {
+        let separator = self.scope().separator.as_deref().unwrap_or("_");
+        let prefix = format!("{}{}", self.case(prefix), separator);
+
+        // Names are compared in lower-case if case-insensitive.
+        let fold = |name: &str| match self.snapshot {
+            Some(_) => name.to_lowercase(),
+            None => name.to_owned(),
+        };
+        let prefix = fold(&prefix);
+        let known: HashSet<String> = self.names.iter().map(|name| fold(name)).collect();
+
+        let mut unused: Vec<String> = self
+            .source
+            .vars()
+            .into_iter()
+            .map(|(name, _)| name)
+            .filter(|name| {
+                let folded = fold(name);
+                folded.starts_with(&prefix) && !known.contains(&folded)
+            })
+            .collect();
+        unused.sort();
+        unused
+    }
+
+    /// Records the environment variables under `prefix` that were never looked up as [`LoadError::Unknown`].
+    fn check_unknown(&mut self, prefix: &str) {
+        for name in self.unused_vars(prefix) {
+            warn!("econf: warning: {} is not used", name);
+            self.errors.push(LoadError::Unknown { name });
+        }
+    }
+
+    /// Logs and records that `name` is not found.
+    fn not_found(&mut self, name: &str) {
+        let level = if self.verbose {
+            Level::Info
+        } else {
+            Level::Trace
+        };
+        log!(level, "econf: loading {}: not found", name);
+        self.record(name, LoadStatus::NotFound);
+    }
+
+    /// Logs and records the value loaded from `name`.
+    ///
+    /// The default value is not counted as loaded, since it doesn't come from the environment.
+    fn found(&mut self, name: &str, value: &str, default: bool) {
+        if default {
+            self.record(name, LoadStatus::Defaulted);
+        } else {
+            info!("econf: loading {}: found {}", name, self.shown(value));
+            self.applied += 1;
+            self.record(name, LoadStatus::Loaded);
+        }
+    }
+
+    fn record(&mut self, name: &str, status: LoadStatus) {
+        self.records.push(LoadRecord {
+            name: name.to_owned(),
+            status,
+        });
+    }
+
+    /// Returns the number of environment variables loaded so far, i.e. the values overridden by the environment.
     ///
     /// ```
     /// # use econf::Loader;
     /// let mut loader = Loader::new();
     ///
-    /// assert!(!loader.is_duplicated("PREFIX_X"));
-    /// assert!(!loader.is_duplicated("PREFIX_Y"));
-    /// assert!(loader.is_duplicated("PREFIX_X"));
+    /// std::env::set_var("LOADED_X", "2");
+    /// std::env::set_var("LOADED_Z", "A");
+    ///
+    /// loader.load_from_str(1, "LOADED_X");
+    /// loader.load_from_str(1, "LOADED_Y");
+    /// loader.load_from_str(1, "LOADED_Z");
+    /// assert_eq!(loader.loaded_count(), 1);
     /// ```
     ///
-    pub fn is_duplicated(&mut self, name: &str) -> bool {
-        !self.names.insert(name.into())
+    pub fn loaded_count(&self) -> usize {
+        self.applied
     }
 
-    /// Loads an environment variable and converts it to a specific type.
+    /// Returns a [`Report`] of the loading so far.
+    pub fn report(&self) -> Report {
+        Report::new(
+            self.records.clone(),
+            self.errors.clone(),
+            self.subtrees.clone(),
+        )
+    }
+
+    /// Summarizes the loading so far into a human-readable report.
     ///
-    /// The function does the following:
+    /// Same as the [`Display`] of [`report`](Self::report).
     ///
-    /// * Checks the duplication of environment variable names loaded so far (case insensitive)
-    /// * Loads the environment variable (look up `name` as upper-case)
-    /// * Calls `map` function to convert the loaded string to a specific type.
+    /// ```
+    /// # use econf::{LoadEnv, Loader};
+    /// #[derive(LoadEnv)]
+    /// struct A {
+    ///     x: u32,
+    ///     y: u32,
+    /// }
     ///
-    /// If loading/conversion is successful, the function returns the new value loaded. Otherwise, returns `fallback`.
+    /// std::env::set_var("REPORT_X", "2");
+    ///
+    /// let mut loader = Loader::new();
+    /// let a = A { x: 1, y: 1 }.load("REPORT", &mut loader);
+    ///
+    /// assert_eq!(
+    ///     loader.into_report(),
+    ///     "econf: 1 loaded, 1 not found, 0 errors, 0 duplicates\n  loaded: REPORT_X"
+    /// );
+    /// ```
+    ///
+    pub fn into_report(self) -> String {
+        self.report().to_string()
+    }
+
+    /// Looks up the environment variable `name`, then the same name under the fallback prefixes.
+    ///
+    /// Returns the name actually found and its value.
+    fn lookup_env(&self, name: &str) -> Option<(String, String)> {
+        if let Some(found) = self.var(name) {
+            return Some(found);
+        }
+
+        let (primary, fallbacks) = self.scope().prefixes.split_first()?;
+        let primary = self.case(primary);
+        let rest = name.strip_prefix(primary.as_str())?;
+        let separator = self.scope().separator.as_deref().unwrap_or("_");
+        if !rest.is_empty() && !rest.starts_with(separator) {
+            return None;
+        }
+
+        fallbacks.iter().find_map(|prefix| {
+            let name = format!("{}{}", self.case(prefix), rest);
+            self.var(&name)
+        })
+    }
+
+    /// Reads the environment variable `name` from the source, ignoring case if [`case_insensitive`](Self::case_insensitive).
+    ///
+    /// Returns the name actually found and its value.
+    fn var(&self, name: &str) -> Option<(String, String)> {
+        match &self.snapshot {
+            Some(snapshot) => snapshot.get(&name.to_lowercase()).cloned(),
+            None => self.source.var(name).map(|s| (name.to_owned(), s)),
+        }
+    }
+
+    /// Looks up `name` by [`lookup_env`](Self::lookup_env), then the default value of the current scope.
+    ///
+    /// An empty value is ignored if [`skip_if_empty`](Self::skip_if_empty), and so is a value in [`null_tokens`](Self::null_tokens).
+    /// Returns the name actually found, its value and whether the value is the default.
+    fn lookup(&self, name: &str) -> Option<(String, String, bool)> {
+        self.lookup_env(name)
+            .filter(|(name, value)| {
+                let skip = self.scope().skip_if_empty && value.is_empty();
+                if skip {
+                    info!("econf: loading {}: empty, ignored", name);
+                }
+                !skip
+            })
+            .filter(|(name, value)| {
+                let null = self.null_tokens.contains(value);
+                if null {
+                    info!("econf: loading {}: {}, ignored", name, value);
+                }
+                !null
+            })
+            .map(|(name, value)| (name, value, false))
+            .or_else(|| match &self.scope().default {
+                Some((default_name, value)) if default_name == name => {
+                    info!(
+                        "econf: loading {}: not found, using default {}",
+                        name,
+                        self.shown(value)
+                    );
+                    Some((name.to_owned(), value.clone(), true))
+                }
+                _ => None,
+            })
+    }
+
+    /// Reads the file at the path given by `name` with the suffix `_FILE`, for [`file_fallback`](Self::file_fallback).
+    ///
+    /// Returns the name of the environment variable giving the path and the trimmed contents of the file.
+    fn lookup_file(&mut self, name: &str) -> Result<Option<(String, String)>, LoadError> {
+        let file = self.case(&self.join(name, "FILE"));
+        let Some((file, path)) = self.lookup_env(&file) else {
+            return Ok(None);
+        };
+        // Marked as looked up, so that it is not reported as unused.
+        self.names.insert(file.clone());
+
+        match std::fs::read_to_string(&path) {
+            Ok(s) => Ok(Some((file, s.trim().to_owned()))),
+            Err(e) => {
+                let msg = format!("failed to read {}: {}", path, e);
+                error!("econf: loading {}: {}", file, msg);
+                self.record(&file, LoadStatus::Failed);
+                Err(LoadError::Parse {
+                    name: file,
+                    value: path,
+                    msg,
+                })
+            }
+        }
+    }
+
+    /// Converts `name` to upper-case unless [`verbatim`](Self::verbatim).
+    ///
+    /// Used for `#[econf(case = "preserve")]` to convert the path given by the parent before keeping the case of the fields.
     ///
     /// ```
     /// # use econf::Loader;
     /// let mut loader = Loader::new();
     ///
-    /// std::env::set_var("BAR", "2");
-    /// std::env::set_var("BUZZ", "A");
+    /// assert_eq!(loader.case("app_mixedCase"), "APP_MIXEDCASE");
+    /// loader.enter();
+    /// loader.verbatim();
+    /// assert_eq!(loader.case("app_mixedCase"), "app_mixedCase");
+    /// loader.leave();
+    /// ```
     ///
-    /// assert_eq!(loader.load_and_map(1, "FOO", |v| v.parse()), 1);
-    /// assert_eq!(loader.load_and_map(1, "BAR", |v| v.parse()), 2);
-    /// assert_eq!(loader.load_and_map(1, "BUZZ", |v| v.parse()), 1);
+    pub fn case(&self, name: &str) -> String {
+        if self.scope().verbatim {
+            name.to_owned()
+        } else {
+            name.to_uppercase()
+        }
+    }
+
+    /// Loads each value of a map field-by-field under its key.
+    ///
+    /// The value of each existing key is loaded by [`LoadEnv::load`] with the key joined to `path`,
+    /// e.g. the value at `primary` of `APP_DBS` is loaded from `APP_DBS_PRIMARY_*`. No entry is added or removed.
+    /// Used for `#[econf(keyed)]`.
+    ///
+    /// ```
+    /// # use econf::Loader;
+    /// # use std::collections::HashMap;
+    /// let mut loader = Loader::new();
+    ///
+    /// std::env::set_var("KEYED_PRIMARY", "2");
+    ///
+    /// let map = HashMap::from([("primary".to_owned(), 1), ("replica".to_owned(), 1)]);
+    /// let map = loader.load_keyed(map, "KEYED");
+    /// assert_eq!(map["primary"], 2);
+    /// assert_eq!(map["replica"], 1);
     /// ```
     ///
-    pub fn load_and_map<T, F, E>(&mut self, fallback: T, name: &str, map: F) -> T
+    pub fn load_keyed<M, K, V>(&mut self, map: M, path: &str) -> M
     where
-        F: FnOnce(&str) -> Result<T, E>,
-        E: Display,
+        M: IntoIterator<Item = (K, V)> + FromIterator<(K, V)>,
+        K: Display,
+        V: LoadEnv,
     {
-        let name = name.to_uppercase();
+        map.into_iter()
+            .map(|(k, v)| {
+                let name = self.join(path, &k.to_string());
+                let v = v.load(&name, self);
+                (k, v)
+            })
+            .collect()
+    }
 
-        if self.is_duplicated(&name) {
-            warn!("econf: warning: {} is ambiguous", name);
+    /// Loads each element of a list field-by-field under its index.
+    ///
+    /// Each existing element is loaded by [`LoadEnv::load`] with the index joined to `path`,
+    /// e.g. the first element of `APP_SERVERS` is loaded from `APP_SERVERS_0_*`. The length is kept,
+    /// so no element can be added this way. Used for `#[econf(indexed)]`.
+    ///
+    /// ```
+    /// # use econf::Loader;
+    /// let mut loader = Loader::new();
+    ///
+    /// std::env::set_var("INDEXED_1", "2");
+    ///
+    /// let list = loader.load_indexed(vec![1, 1], "INDEXED");
+    /// assert_eq!(list, [1, 2]);
+    /// ```
+    ///
+    pub fn load_indexed<C, V>(&mut self, list: C, path: &str) -> C
+    where
+        C: IntoIterator<Item = V> + FromIterator<V>,
+        V: LoadEnv,
+    {
+        list.into_iter()
+            .enumerate()
+            .map(|(i, v)| {
+                let name = self.join(path, &i.to_string());
+                v.load(&name, self)
+            })
+            .collect()
+    }
+
+    /// Loads each element of a list field-by-field under its index, growing the list up to the highest index set.
+    ///
+    /// Works like [`load_indexed`](Self::load_indexed), but if an environment variable is set at an index past the end,
+    /// e.g. `APP_SERVERS_2_HOST` for a list of one element, the list is extended with `V::default()` to reach it.
+    /// The indices in between that are not set keep the existing elements, or the default ones if added.
+    /// Used for `#[econf(indexed, sparse)]`.
+    ///
+    /// ```
+    /// # use econf::Loader;
+    /// let mut loader = Loader::new();
+    ///
+    /// std::env::set_var("SPARSE_0", "2");
+    /// std::env::set_var("SPARSE_3", "5");
+    ///
+    /// let list = loader.load_sparse(vec![1, 1], "SPARSE");
+    /// assert_eq!(list, [2, 1, 0, 5]);
+    /// ```
+    ///
+    pub fn load_sparse<V>(&mut self, mut list: Vec<V>, path: &str) -> Vec<V>
+    where
+        V: LoadEnv + Default,
+    {
+        let separator = self.scope().separator.as_deref().unwrap_or("_").to_owned();
+        let len = self
+            .names_under(path)
+            .iter()
+            .filter_map(|(_, rest)| rest.split(separator.as_str()).next()?.parse::<usize>().ok())
+            .map(|i| i + 1)
+            .max()
+            .unwrap_or(0);
+        if len > list.len() {
+            list.resize_with(len, V::default);
         }
+        self.load_indexed(list, path)
+    }
+
+    /// Collects the environment variables under `path` into a map, with the keys in lower-case.
+    ///
+    /// Each environment variable named `path` followed by the separator and a key, e.g. `APP_LABELS_FOO`,
+    /// is loaded in yaml format and inserted at the lower-cased key `foo`. Existing keys are lower-cased as well,
+    /// so keys differing only in case are merged into one entry. Entries not found in the environment are kept.
+    /// Used for `#[econf(ci_keys)]`.
+    ///
+    /// ```
+    /// # use econf::Loader;
+    /// # use std::collections::HashMap;
+    /// let mut loader = Loader::new();
+    ///
+    /// std::env::set_var("CI_KEYS_Foo", "2");
+    ///
+    /// let map = HashMap::from([("FOO".to_owned(), 1), ("bar".to_owned(), 1)]);
+    /// let map = loader.load_ci_keys(map, "CI_KEYS");
+    /// assert_eq!(map, HashMap::from([("foo".to_owned(), 2), ("bar".to_owned(), 1)]));
+    /// ```
+    ///
+    pub fn load_ci_keys<V>(&mut self, map: HashMap<String, V>, path: &str) -> HashMap<String, V>
+    where
+        V: DeserializeOwned,
+    {
+        let mut map: HashMap<String, V> = map
+            .into_iter()
+            .map(|(k, v)| (k.to_lowercase(), v))
+            .collect();
+        let mut keys = HashSet::new();
+
+        let names = self.names_under(path);
 
-        match std::env::var(&name) {
-            Ok(s) => match map(&s) {
+        // The names are already in the case of the environment.
+        self.enter();
+        self.verbatim();
+        for (name, rest) in names {
+            let key = rest.to_lowercase();
+            if !keys.insert(key.clone()) {
+                warn!("econf: warning: {} is merged into the key {}", name, key);
+            }
+            match self.try_load_and_map(&name, |s| serde_yaml::from_str(s)) {
                 Ok(v) => {
-                    info!("econf: loading {}: found {}", name, s);
-                    v
+                    map.insert(key, v);
                 }
+                Err(e) => self.errors.push(e),
+            }
+        }
+        self.leave();
+
+        map
+    }
+
+    /// Collects the environment variables under `path` into a nested map, splitting the rest of the names by `sep`.
+    ///
+    /// Each environment variable named `path` followed by the separator and keys joined by `sep`, e.g. `APP_CFG_A_B`,
+    /// is loaded in yaml format and inserted at the lower-cased keys, e.g. `{a: {b: ...}}`.
+    /// Existing entries are kept unless overridden. Used for `#[econf(nested_keys)]`.
+    ///
+    /// ```
+    /// # use econf::Loader;
+    /// # use std::collections::HashMap;
+    /// let mut loader = Loader::new();
+    ///
+    /// std::env::set_var("NESTED_KEYS_A_B", "1");
+    /// std::env::set_var("NESTED_KEYS_A_C", "x");
+    ///
+    /// let map = loader.load_nested_keys(HashMap::new(), "NESTED_KEYS", "_");
+    /// assert_eq!(map["a"], serde_yaml::from_str::<serde_yaml::Value>("{b: 1, c: x}").unwrap());
+    /// ```
+    ///
+    pub fn load_nested_keys(
+        &mut self,
+        mut map: HashMap<String, Value>,
+        path: &str,
+        sep: &str,
+    ) -> HashMap<String, Value> {
+        let names = self.names_under(path);
+
+        // The names are already in the case of the environment.
+        self.enter();
+        self.verbatim();
+        for (name, rest) in names {
+            let keys: Vec<String> = rest.split(sep).map(|key| key.to_lowercase()).collect();
+            if keys.iter().any(|key| key.is_empty()) {
+                warn!("econf: warning: {} has an empty key, ignored", name);
+                continue;
+            }
+            let value = match self.try_load_and_map(&name, |s| serde_yaml::from_str::<Value>(s)) {
+                Ok(v) => v,
                 Err(e) => {
-                    error!("econf: loading {}: error on parsing \"{}\": {}", name, s, e);
-                    fallback
+                    self.errors.push(e);
+                    continue;
                 }
-            },
-            Err(_) => {
-                info!("econf: loading {}: not found", name);
-                fallback
+            };
+
+            let (first, rest) = keys.split_first().expect("split yields a key");
+            let mut entry = map.entry(first.clone()).or_insert(Value::Null);
+            for key in rest {
+                if !entry.is_mapping() {
+                    if !entry.is_null() {
+                        warn!("econf: warning: {} replaces a value with a map", name);
+                    }
+                    *entry = Value::Mapping(Mapping::new());
+                }
+                entry = entry
+                    .as_mapping_mut()
+                    .expect("mapping")
+                    .entry(Value::String(key.clone()))
+                    .or_insert(Value::Null);
             }
+            *entry = value;
         }
+        self.leave();
+
+        map
+    }
+
+    /// Lists the environment variables under `path`, sorted by name, with the rest of the names after the separator.
+    fn names_under(&self, path: &str) -> Vec<(String, String)> {
+        let separator = self.scope().separator.as_deref().unwrap_or("_");
+        let prefix = format!("{}{}", self.case(path), separator);
+
+        let mut names: Vec<(String, String)> = self
+            .source
+            .vars()
+            .into_iter()
+            .filter_map(|(name, _)| {
+                let rest = name.strip_prefix(&prefix).filter(|rest| !rest.is_empty())?;
+                Some((name.clone(), rest.to_owned()))
+            })
+            .collect();
+        names.sort();
+        names
     }
 
     /// Loads an environment variable in yaml format then deserializes it to a specific type.
     ///
     /// The function is used to load compound types and collections. Since the yaml is the superset of json,
-    /// the function is usable to parse json format.
+    /// the function is usable to parse json format. Strict json is parsed instead if [`set_format`](Self::set_format) is given [`Format::Json`].
+    ///
+    /// If the environment variable is not set and [`file_fallback`](Self::file_fallback) is enabled, the file at the path
+    /// given by the environment variable with the suffix `_FILE` is read instead, e.g. `/etc/rules.yaml` given by `RULES_FILE` for `RULES`.
     ///
     /// If loading/conversion is successful, the function returns the new value loaded. Otherwise, returns `fallback`.
     ///
@@ -117,7 +1563,19 @@ impl Loader {
     where
         T: DeserializeOwned,
     {
-        self.load_and_map(fallback, name, |s| serde_yaml::from_str(s))
+        self.load_from_yaml_and_map(fallback, name, Ok)
+    }
+
+    /// Works like [`load_from_yaml`](Self::load_from_yaml), then converts the deserialized value by `map`.
+    ///
+    /// A conversion error is handled in the same way as a parse error.
+    pub(crate) fn load_from_yaml_and_map<T, U, F>(&mut self, fallback: T, name: &str, map: F) -> T
+    where
+        U: DeserializeOwned,
+        F: FnOnce(U) -> Result<T, String>,
+    {
+        let format = self.format;
+        self.load_and_map(fallback, name, |s| format.parse(s).and_then(map))
     }
 
     /// Loads an environment variable then converts it to a specific type using [`from_str`](std::str::FromStr::from_str).
@@ -147,4 +1605,137 @@ impl Loader {
     {
         self.load_and_map(fallback, name, |s| T::from_str(s))
     }
+
+    /// Loads an environment variable as an [`OsString`], then converts it by `From`.
+    ///
+    /// Unlike [`load_from_str`](Self::load_from_str), a value that is not valid UTF-8 is loaded as is
+    /// instead of being ignored. Such a value is only looked up at `name` itself, ignoring the fallback prefixes
+    /// and [`case_insensitive`](Self::case_insensitive). The file given by `_FILE` is read as well
+    /// if [`file_fallback`](Self::file_fallback) is enabled.
+    ///
+    /// ```
+    /// # use econf::Loader;
+    /// # use std::path::PathBuf;
+    /// let mut loader = Loader::new();
+    ///
+    /// std::env::set_var("LOAD_OS_PATH", "/var/lib/app");
+    ///
+    /// assert_eq!(loader.load_os(PathBuf::new(), "LOAD_OS_PATH"), PathBuf::from("/var/lib/app"));
+    /// ```
+    ///
+    pub fn load_os<T>(&mut self, fallback: T, name: &str) -> T
+    where
+        T: From<OsString>,
+    {
+        let name = self.case(name);
+
+        if self.is_duplicated(&name) {
+            warn!("econf: warning: {} is ambiguous", name);
+            self.errors
+                .push(LoadError::Duplicate { name: name.clone() });
+        }
+
+        // A value that is valid UTF-8 is found by `lookup`, so only the others are taken from `var_os`.
+        let found = match self.lookup(&name) {
+            Some((name, s, default)) => Some((name, OsString::from(s), default)),
+            None => self
+                .source
+                .var_os(&name)
+                .filter(|s| s.to_str().is_none())
+                .map(|s| (name.clone(), s, false)),
+        };
+        let found = match found {
+            None if self.file_fallback => match self.lookup_file(&name) {
+                Ok(found) => found.map(|(name, s)| (name, OsString::from(s), false)),
+                Err(e) => {
+                    self.errors.push(e);
+                    return fallback;
+                }
+            },
+            found => found,
+        };
+
+        match found {
+            Some((name, s, default)) => {
+                self.found(&name, &s.to_string_lossy(), default);
+                T::from(s)
+            }
+            None => {
+                self.not_found(&name);
+                fallback
+            }
+        }
+    }
+
+    /// Loads an environment variable with `$VAR`/`${VAR}` references expanded, then converts it by [`from_str`](std::str::FromStr::from_str).
+    ///
+    /// The references are read from the source of the loader. `$$` is expanded to `$`.
+    /// A reference to a missing variable is left as is, or fails the loading if `strict`.
+    /// Used for `#[econf(expand_vars)]`.
+    ///
+    /// ```
+    /// # use econf::Loader;
+    /// # use std::path::PathBuf;
+    /// let mut loader = Loader::new();
+    ///
+    /// std::env::set_var("EXPAND_HOME", "/home/user");
+    /// std::env::set_var("EXPAND_DATA", "${EXPAND_HOME}/data/$EXPAND_MISSING");
+    ///
+    /// assert_eq!(loader.load_expanded(PathBuf::new(), "EXPAND_DATA", false), PathBuf::from("/home/user/data/$EXPAND_MISSING"));
+    /// assert_eq!(loader.load_expanded(PathBuf::new(), "EXPAND_DATA", true), PathBuf::new());
+    /// ```
+    ///
+    pub fn load_expanded<T>(&mut self, fallback: T, name: &str, strict: bool) -> T
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        let source = self.source.clone();
+        self.load_and_map(fallback, name, |s| {
+            expand(&*source, s, strict)?
+                .parse()
+                .map_err(|e: T::Err| e.to_string())
+        })
+    }
+}
+
+/// Expands `$VAR`/`${VAR}` references in `s` by the variables in `source`.
+fn expand(source: &dyn EnvSource, s: &str, strict: bool) -> Result<String, String> {
+    let mut expanded = String::new();
+    let mut rest = s;
+
+    while let Some(i) = rest.find('$') {
+        expanded.push_str(&rest[..i]);
+        let after = &rest[i + 1..];
+
+        let (name, reference, next) = if let Some(after) = after.strip_prefix('$') {
+            expanded.push('$');
+            rest = after;
+            continue;
+        } else if let Some(braced) = after.strip_prefix('{') {
+            match braced.find('}') {
+                Some(end) => (&braced[..end], &rest[i..i + end + 3], &braced[end + 1..]),
+                None => ("", "$", after),
+            }
+        } else {
+            let end = after
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                .unwrap_or(after.len());
+            (&after[..end], &rest[i..i + end + 1], &after[end..])
+        };
+
+        if name.is_empty() {
+            expanded.push_str(reference);
+        } else {
+            match source.var(name) {
+                Some(value) => expanded.push_str(&value),
+                None if strict => return Err(format!("{} is not found", name)),
+                None => expanded.push_str(reference),
+            }
+        }
+        rest = next;
+    }
+
+    expanded.push_str(rest);
+    Ok(expanded)
 }
@@ -0,0 +1,273 @@
+use std::rc::Rc;
+
+use crate::source::default_source;
+use crate::{EnvSource, Format, LoadEnv, LoadError, Loader};
+
+/// Options for [`load_with_options`](crate::load_with_options).
+///
+/// The defaults are the same as [`load`](crate::load): names are upper-case, joined by `_` and matched exactly,
+/// read from the process environment, and unknown environment variables and ambiguous names don't fail loading.
+///
+/// ```
+/// # use econf::{Format, LoadOptions};
+/// let opts = LoadOptions::new()
+///     .separator("__")
+///     .case_insensitive(true)
+///     .deny_unknown(true)
+///     .format(Format::Json);
+/// ```
+///
+#[derive(Clone)]
+pub struct LoadOptions {
+    separator: Option<String>,
+    verbatim: bool,
+    case_insensitive: bool,
+    source: Option<Rc<dyn EnvSource>>,
+    fallback_prefixes: Vec<String>,
+    deny_unknown: bool,
+    deny_duplicates: bool,
+    skip_if_empty: bool,
+    null_tokens: Vec<String>,
+    format: Format,
+    verbose: bool,
+    file_fallback: bool,
+}
+
+impl Default for LoadOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LoadOptions {
+    /// Create the default options.
+    pub fn new() -> Self {
+        Self {
+            separator: None,
+            verbatim: false,
+            case_insensitive: false,
+            source: None,
+            fallback_prefixes: vec![],
+            deny_unknown: false,
+            deny_duplicates: false,
+            skip_if_empty: false,
+            null_tokens: vec![],
+            format: Format::Yaml,
+            verbose: true,
+            file_fallback: false,
+        }
+    }
+
+    /// Joins the names of nested fields by `separator` instead of `_`. See [`Loader::separator`].
+    pub fn separator(mut self, separator: &str) -> Self {
+        self.separator = Some(separator.to_owned());
+        self
+    }
+
+    /// Looks up names as is, without converting them to upper-case. See [`Loader::verbatim`].
+    pub fn verbatim(mut self, enabled: bool) -> Self {
+        self.verbatim = enabled;
+        self
+    }
+
+    /// Matches names ignoring case. See [`Loader::case_insensitive`].
+    pub fn case_insensitive(mut self, enabled: bool) -> Self {
+        self.case_insensitive = enabled;
+        self
+    }
+
+    /// Reads environment variables from `source` instead of the process environment. See [`Loader::with_source`].
+    pub fn source<S>(mut self, source: S) -> Self
+    where
+        S: EnvSource + 'static,
+    {
+        self.source = Some(Rc::new(source));
+        self
+    }
+
+    /// Looks up the names not found under the prefix under each of `prefixes` in order. See [`Loader::set_prefixes`].
+    pub fn fallback_prefixes(mut self, prefixes: &[&str]) -> Self {
+        self.fallback_prefixes = prefixes.iter().map(|&prefix| prefix.to_owned()).collect();
+        self
+    }
+
+    /// Fails on the environment variables under the prefix that are not loaded to any field. See [`Loader::deny_unknown`].
+    pub fn deny_unknown(mut self, enabled: bool) -> Self {
+        self.deny_unknown = enabled;
+        self
+    }
+
+    /// Fails on ambiguous names, i.e. two fields loaded from the same environment variable, instead of only warning.
+    pub fn deny_duplicates(mut self, enabled: bool) -> Self {
+        self.deny_duplicates = enabled;
+        self
+    }
+
+    /// Ignores environment variables set to an empty string. See [`Loader::skip_if_empty`].
+    pub fn skip_if_empty(mut self, enabled: bool) -> Self {
+        self.skip_if_empty = enabled;
+        self
+    }
+
+    /// Ignores environment variables set to one of `tokens`. See [`Loader::null_tokens`].
+    pub fn null_tokens(mut self, tokens: &[&str]) -> Self {
+        self.null_tokens = tokens.iter().map(|&token| token.to_owned()).collect();
+        self
+    }
+
+    /// Parses containers in `format`. See [`Loader::set_format`].
+    pub fn format(mut self, format: Format) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Logs the environment variables not found at info level if `enabled` (the default), or at trace level otherwise.
+    /// See [`Loader::verbose`].
+    pub fn verbose(mut self, enabled: bool) -> Self {
+        self.verbose = enabled;
+        self
+    }
+
+    /// Reads the value from the file given by the environment variable with the suffix `_FILE` if unset. See [`Loader::file_fallback`].
+    pub fn file_fallback(mut self, enabled: bool) -> Self {
+        self.file_fallback = enabled;
+        self
+    }
+
+    /// Whether ambiguous names fail loading.
+    pub(crate) fn denies_duplicates(&self) -> bool {
+        self.deny_duplicates
+    }
+
+    /// Create a loader applying the options to load under `prefix`.
+    pub(crate) fn loader(&self, prefix: &str) -> Loader {
+        let source = self.source.clone().unwrap_or_else(default_source);
+        let mut loader = Loader::from_source(source);
+
+        if let Some(separator) = &self.separator {
+            loader.separator(separator);
+        }
+        if self.verbatim {
+            loader.verbatim();
+        }
+        if self.case_insensitive {
+            loader.case_insensitive(true);
+        }
+        if !self.fallback_prefixes.is_empty() {
+            let prefixes: Vec<&str> = std::iter::once(prefix)
+                .chain(self.fallback_prefixes.iter().map(String::as_str))
+                .collect();
+            loader.set_prefixes(&prefixes);
+        }
+        if self.skip_if_empty {
+            loader.skip_if_empty();
+        }
+        let tokens: Vec<&str> = self.null_tokens.iter().map(String::as_str).collect();
+        loader.null_tokens(&tokens);
+        loader.deny_unknown(self.deny_unknown);
+        loader.set_format(self.format);
+        loader.verbose(self.verbose);
+        loader.file_fallback(self.file_fallback);
+        loader
+    }
+}
+
+/// A builder to load environment variables to a struct with options.
+///
+/// [`load`](crate::load) is a shortcut for the default builder. Options other than the ones below are given by [`LoadOptions`].
+///
+/// ```
+/// # use econf::{Builder, LoadEnv};
+/// #[derive(LoadEnv)]
+/// struct A {
+///     inner: B,
+/// }
+///
+/// #[derive(LoadEnv)]
+/// struct B {
+///     port: u16,
+/// }
+///
+/// std::env::set_var("builder__inner__port", "80");
+///
+/// let a = Builder::new()
+///     .prefix("BUILDER")
+///     .separator("__")
+///     .case_insensitive(true)
+///     .load(A { inner: B { port: 8080 } });
+/// assert_eq!(a.inner.port, 80);
+/// ```
+///
+#[derive(Clone, Default)]
+pub struct Builder {
+    prefix: String,
+    opts: LoadOptions,
+}
+
+impl Builder {
+    /// Create the builder with the empty prefix and the default options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads under `prefix`.
+    pub fn prefix(mut self, prefix: &str) -> Self {
+        self.prefix = prefix.to_owned();
+        self
+    }
+
+    /// Joins the names of nested fields by `separator` instead of `_`. See [`LoadOptions::separator`].
+    pub fn separator(mut self, separator: &str) -> Self {
+        self.opts = self.opts.separator(separator);
+        self
+    }
+
+    /// Parses containers in `format`. See [`LoadOptions::format`].
+    pub fn format(mut self, format: Format) -> Self {
+        self.opts = self.opts.format(format);
+        self
+    }
+
+    /// Matches names ignoring case. See [`LoadOptions::case_insensitive`].
+    pub fn case_insensitive(mut self, enabled: bool) -> Self {
+        self.opts = self.opts.case_insensitive(enabled);
+        self
+    }
+
+    /// Reads environment variables from `source` instead of the process environment. See [`LoadOptions::source`].
+    pub fn source<S>(mut self, source: S) -> Self
+    where
+        S: EnvSource + 'static,
+    {
+        self.opts = self.opts.source(source);
+        self
+    }
+
+    /// Replaces all the options by `opts`.
+    pub fn options(mut self, opts: LoadOptions) -> Self {
+        self.opts = opts;
+        self
+    }
+
+    /// Create a loader applying the options, to load under the prefix by hand.
+    pub fn loader(&self) -> Loader {
+        self.opts.loader(&self.prefix)
+    }
+
+    /// Load environment variables to `data`, keeping the original values on failures as [`load`](crate::load).
+    pub fn load<T>(self, data: T) -> T
+    where
+        T: LoadEnv,
+    {
+        let mut loader = self.loader();
+        data.load(&self.prefix, &mut loader)
+    }
+
+    /// Load environment variables to `data`, failing on malformed values as [`load_with_options`](crate::load_with_options).
+    pub fn try_load<T>(self, data: T) -> Result<T, Vec<LoadError>>
+    where
+        T: LoadEnv,
+    {
+        crate::load_with_options(data, &self.prefix, self.opts)
+    }
+}
@@ -0,0 +1,437 @@
+//! Parsers usable with [`Loader::load_and_map`](crate::Loader::load_and_map).
+//!
+//! These functions back the helper attributes of the [`LoadEnv`](crate::LoadEnv) derive macro,
+//! and can be used directly in manual [`LoadEnv`](crate::LoadEnv) implementations.
+
+use std::fmt::Display;
+use std::net::{AddrParseError, Ipv6Addr};
+use std::ops::{Range, RangeInclusive};
+use std::str::FromStr;
+use std::time::Duration;
+
+use serde::de::DeserializeOwned;
+use serde_yaml::value::{Tag, TaggedValue};
+use serde_yaml::Value;
+
+/// Parses a duration given either as fractional seconds or in [`humantime`] format.
+///
+/// A bare number such as `1.5` is interpreted as seconds. Anything else is parsed by
+/// [`humantime::parse_duration`]. Used by `#[econf(duration_float_secs)]`.
+///
+/// ```
+/// # use std::time::Duration;
+/// assert_eq!(econf::parse::duration_float_secs("1.5"), Ok(Duration::from_millis(1500)));
+/// assert_eq!(econf::parse::duration_float_secs("2m"), Ok(Duration::from_secs(120)));
+/// assert!(econf::parse::duration_float_secs("-1.5").is_err());
+/// ```
+///
+pub fn duration_float_secs(s: &str) -> Result<Duration, String> {
+    match s.trim().parse::<f64>() {
+        Ok(secs) => Duration::try_from_secs_f64(secs).map_err(|e| e.to_string()),
+        Err(_) => humantime::parse_duration(s).map_err(|e| e.to_string()),
+    }
+}
+
+/// Parses a duration in [`humantime`] format such as `1m 30s`, or a bare `0`.
+///
+/// This is how `Duration` is loaded. A bare `0` is accepted since it is commonly used to disable timeouts.
+///
+/// ```
+/// # use std::time::Duration;
+/// assert_eq!(econf::parse::duration("1m 30s"), Ok(Duration::from_secs(90)));
+/// assert_eq!(econf::parse::duration("0"), Ok(Duration::ZERO));
+/// assert!(econf::parse::duration("1").is_err());
+/// ```
+///
+pub fn duration(s: &str) -> Result<Duration, String> {
+    match s.trim() {
+        "0" => Ok(Duration::ZERO),
+        s => humantime::parse_duration(s).map_err(|e| e.to_string()),
+    }
+}
+
+/// Parses a human-readable size such as `4KiB` into an integer number of bytes.
+///
+/// A number, optionally grouped by `_` such as `10_000`, is followed by an optional unit, with or without a space in between. Units are case-insensitive:
+/// `B`, the decimal units `KB`, `MB`, `GB`, `TB` (powers of 1000) and the binary units `KiB`, `MiB`, `GiB`, `TiB`
+/// (powers of 1024). The byte count is then converted to `T`, so loading zero into a `NonZero` type or a size
+/// out of the range of `T` is an error. Used by `#[econf(bytesize)]`.
+///
+/// ```
+/// # use std::num::NonZeroUsize;
+/// assert_eq!(econf::parse::bytesize::<u64>("4KiB"), Ok(4096));
+/// assert_eq!(econf::parse::bytesize::<u64>("2 MB"), Ok(2_000_000));
+/// assert_eq!(econf::parse::bytesize::<u64>("512"), Ok(512));
+/// assert_eq!(econf::parse::bytesize::<u64>("10_000_000"), econf::parse::bytesize("10MB"));
+/// assert_eq!(econf::parse::bytesize::<NonZeroUsize>("1k").map(NonZeroUsize::get), Ok(1000));
+/// assert!(econf::parse::bytesize::<NonZeroUsize>("0KiB").is_err());
+/// assert!(econf::parse::bytesize::<u8>("1KiB").is_err());
+/// ```
+///
+pub fn bytesize<T>(s: &str) -> Result<T, String>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    let s = s.trim();
+    let (number, unit) = split_digits(s);
+    let number: u64 = number
+        .parse()
+        .map_err(|e| format!("invalid size \"{}\": {}", s, e))?;
+    let multiplier: u64 = match unit.trim().to_ascii_lowercase().as_str() {
+        "" | "b" => 1,
+        "k" | "kb" => 1_000,
+        "m" | "mb" => 1_000_000,
+        "g" | "gb" => 1_000_000_000,
+        "t" | "tb" => 1_000_000_000_000,
+        "ki" | "kib" => 1 << 10,
+        "mi" | "mib" => 1 << 20,
+        "gi" | "gib" => 1 << 30,
+        "ti" | "tib" => 1 << 40,
+        unit => return Err(format!("unknown unit \"{}\" in \"{}\"", unit, s)),
+    };
+    let bytes = number
+        .checked_mul(multiplier)
+        .ok_or_else(|| format!("size \"{}\" is too large", s))?;
+    bytes
+        .to_string()
+        .parse()
+        .map_err(|e| format!("size \"{}\" ({} bytes): {}", s, bytes, e))
+}
+
+/// Parses a human-readable integer such as `10_000` or `10k`.
+///
+/// Digits may be grouped by `_`, and followed by an optional case-insensitive suffix, with or without a space in between:
+/// `K`, `M`, `G` and `T` (powers of 1000). A leading `-` or `+` is accepted. The result is then converted to `T`,
+/// so a number out of the range of `T` is an error. Used by `#[econf(human_number)]`.
+///
+/// ```
+/// assert_eq!(econf::parse::number::<u32>("10_000"), Ok(10_000));
+/// assert_eq!(econf::parse::number::<u64>("10M"), Ok(10_000_000));
+/// assert_eq!(econf::parse::number::<i32>("-2k"), Ok(-2_000));
+/// assert!(econf::parse::number::<u32>("10X").is_err());
+/// assert!(econf::parse::number::<u8>("1k").is_err());
+/// ```
+///
+pub fn number<T>(s: &str) -> Result<T, String>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    let s = s.trim();
+    let (sign, unsigned) = match s.strip_prefix('-') {
+        Some(rest) => ("-", rest),
+        None => ("", s.strip_prefix('+').unwrap_or(s)),
+    };
+    let (number, suffix) = split_digits(unsigned);
+    let number: u128 = number
+        .parse()
+        .map_err(|e| format!("invalid number \"{}\": {}", s, e))?;
+    let multiplier: u128 = match suffix.trim().to_ascii_lowercase().as_str() {
+        "" => 1,
+        "k" => 1_000,
+        "m" => 1_000_000,
+        "g" => 1_000_000_000,
+        "t" => 1_000_000_000_000,
+        suffix => return Err(format!("unknown suffix \"{}\" in \"{}\"", suffix, s)),
+    };
+    let value = number
+        .checked_mul(multiplier)
+        .ok_or_else(|| format!("number \"{}\" is too large", s))?;
+    format!("{}{}", sign, value)
+        .parse()
+        .map_err(|e| format!("number \"{}\": {}", s, e))
+}
+
+/// Splits `s` into the leading digits, with `_` separators removed, and the rest.
+fn split_digits(s: &str) -> (String, &str) {
+    let split = s
+        .find(|c: char| !c.is_ascii_digit() && c != '_')
+        .unwrap_or(s.len());
+    let (digits, rest) = s.split_at(split);
+    (digits.replace('_', ""), rest)
+}
+
+/// Parses an IPv6 address, discarding the zone id.
+///
+/// A zone id such as `%eth0` in `fe80::1%eth0` is removed before parsing, since [`Ipv6Addr`] cannot hold it.
+/// Used by `#[econf(ipv6_scope)]`.
+///
+/// ```
+/// # use std::net::Ipv6Addr;
+/// assert_eq!(econf::parse::ipv6_scope("fe80::1%eth0"), "fe80::1".parse::<Ipv6Addr>());
+/// assert_eq!(econf::parse::ipv6_scope("::1"), Ok(Ipv6Addr::LOCALHOST));
+/// ```
+///
+pub fn ipv6_scope(s: &str) -> Result<Ipv6Addr, AddrParseError> {
+    let addr = s.split_once('%').map_or(s, |(addr, _)| addr);
+    addr.parse()
+}
+
+/// Parses an IP address, accepting `*` as the unspecified address `0.0.0.0`.
+///
+/// `*` is commonly used to mean all interfaces. Anything else, including `::`, is parsed as is.
+/// Used by `#[econf(bind_alias)]` for `IpAddr` and `Ipv4Addr`.
+///
+/// ```
+/// # use std::net::{IpAddr, Ipv4Addr};
+/// assert_eq!(econf::parse::bind_alias("*"), Ok(Ipv4Addr::UNSPECIFIED));
+/// assert_eq!(econf::parse::bind_alias("::"), "::".parse::<IpAddr>());
+/// assert_eq!(econf::parse::bind_alias("127.0.0.1"), Ok(Ipv4Addr::LOCALHOST));
+/// ```
+///
+pub fn bind_alias<T>(s: &str) -> Result<T, T::Err>
+where
+    T: FromStr,
+{
+    match s.trim() {
+        "*" => "0.0.0.0".parse(),
+        _ => s.parse(),
+    }
+}
+
+/// Parses a string separated by `delimiter` into a collection.
+///
+/// Each element is trimmed of whitespace and parsed by [`from_str`](std::str::FromStr::from_str) in order,
+/// then collected into any collection that implements [`FromIterator`], such as `Vec` or `VecDeque`.
+/// An empty or blank string is an empty collection. Used by `#[econf(delimiter)]`.
+///
+/// ```
+/// # use std::collections::VecDeque;
+/// let v: Vec<u32> = econf::parse::delimited("1, 2, 3", ",").unwrap();
+/// assert_eq!(v, vec![1, 2, 3]);
+///
+/// let v: VecDeque<char> = econf::parse::delimited("a;b;c", ";").unwrap();
+/// assert_eq!(v, VecDeque::from(['a', 'b', 'c']));
+///
+/// let v: Vec<String> = econf::parse::delimited("", ",").unwrap();
+/// assert!(v.is_empty());
+/// ```
+///
+pub fn delimited<C, T>(s: &str, delimiter: &str) -> Result<C, T::Err>
+where
+    C: FromIterator<T>,
+    T: FromStr,
+{
+    split(s, delimiter).map(T::from_str).collect()
+}
+
+/// Splits `s` by `delimiter` into trimmed elements, or nothing if `s` is blank.
+fn split<'a>(s: &'a str, delimiter: &'a str) -> impl Iterator<Item = &'a str> {
+    let s = s.trim();
+    let elems = (!s.is_empty()).then(|| s.split(delimiter).map(str::trim));
+    elems.into_iter().flatten()
+}
+
+/// Parses a string separated by `delimiter` into a collection of durations.
+///
+/// Each element is parsed by [`duration`] in order, so `1m,30s,1h` is loaded as one minute, 30 seconds and one hour.
+/// Elements are trimmed and a blank string is an empty collection as in [`delimited`].
+/// Used by `#[econf(delimiter, humantime)]`.
+///
+/// ```
+/// # use std::time::Duration;
+/// let v: Vec<Duration> = econf::parse::delimited_durations("1m,30s,1h", ",").unwrap();
+/// assert_eq!(v, [Duration::from_secs(60), Duration::from_secs(30), Duration::from_secs(3600)]);
+///
+/// assert!(econf::parse::delimited_durations::<Vec<Duration>>("1m,x", ",").is_err());
+/// ```
+///
+pub fn delimited_durations<C>(s: &str, delimiter: &str) -> Result<C, String>
+where
+    C: FromIterator<Duration>,
+{
+    split(s, delimiter)
+        .map(|e| duration(e).map_err(|err| format!("\"{}\": {}", e, err)))
+        .collect()
+}
+
+/// Splits a string into words by the quoting rules of the shell.
+///
+/// Words are separated by whitespace, where single quotes, double quotes and backslash escapes are handled
+/// as in POSIX shells. Unclosed quotes are an error. Used by `#[econf(shell_words)]` with the `shlex` feature.
+///
+/// ```
+/// let v: Vec<String> = econf::parse::shell_words(r#"--foo 'bar baz' "a \"b\"" c\ d"#).unwrap();
+/// assert_eq!(v, ["--foo", "bar baz", "a \"b\"", "c d"]);
+///
+/// assert!(econf::parse::shell_words::<Vec<String>>("'unclosed").is_err());
+/// ```
+///
+#[cfg(feature = "shlex")]
+pub fn shell_words<C>(s: &str) -> Result<C, String>
+where
+    C: FromIterator<String>,
+{
+    shlex::split(s)
+        .map(|words| words.into_iter().collect())
+        .ok_or_else(|| format!("invalid quoting in \"{}\"", s))
+}
+
+/// Parses an optional value by [`from_str`](std::str::FromStr::from_str).
+///
+/// A YAML null token (`null`, `Null`, `NULL`, `~` or an empty string) is parsed as `None`.
+/// Anything else is parsed by `from_str` into `Some`. Unlike the default YAML path of `Option`,
+/// `T` needs to implement only `FromStr`, which suits enums. Used by `#[econf(from_str)]`.
+///
+/// ```
+/// let v: Option<u32> = econf::parse::optional("2").unwrap();
+/// assert_eq!(v, Some(2));
+///
+/// let v: Option<u32> = econf::parse::optional("null").unwrap();
+/// assert_eq!(v, None);
+/// ```
+///
+pub fn optional<T>(s: &str) -> Result<Option<T>, T::Err>
+where
+    T: FromStr,
+{
+    match s.trim() {
+        "" | "~" | "null" | "Null" | "NULL" => Ok(None),
+        _ => T::from_str(s).map(Some),
+    }
+}
+
+/// Parses a string of key-value pairs separated by `delimiter` into a map.
+///
+/// Each pair is split at the first `kv_sep`, then the key and the value are parsed by
+/// [`from_str`](std::str::FromStr::from_str) and collected into any collection that implements [`FromIterator`],
+/// such as `BTreeMap` or `HashMap`. A pair without `kv_sep` is an error.
+/// Keys and values are trimmed and a blank string is an empty map as in [`delimited`].
+/// Used by `#[econf(delimiter, kv_sep = "=")]`.
+///
+/// ```
+/// # use std::collections::BTreeMap;
+/// let m: BTreeMap<String, u32> = econf::parse::delimited_pairs("a=1,b=2", ",", "=").unwrap();
+/// assert_eq!(m, BTreeMap::from([("a".into(), 1), ("b".into(), 2)]));
+///
+/// assert!(econf::parse::delimited_pairs::<BTreeMap<String, u32>, _, _>("a=1,b", ",", "=").is_err());
+/// ```
+///
+pub fn delimited_pairs<C, K, V>(s: &str, delimiter: &str, kv_sep: &str) -> Result<C, String>
+where
+    C: FromIterator<(K, V)>,
+    K: FromStr,
+    K::Err: Display,
+    V: FromStr,
+    V::Err: Display,
+{
+    split(s, delimiter)
+        .map(|pair| {
+            let (k, v) = pair
+                .split_once(kv_sep)
+                .ok_or_else(|| format!("missing \"{}\" in \"{}\"", kv_sep, pair))?;
+            let (k, v) = (k.trim(), v.trim());
+            let k = k.parse().map_err(|e| format!("key \"{}\": {}", k, e))?;
+            let v = v.parse().map_err(|e| format!("value \"{}\": {}", v, e))?;
+            Ok((k, v))
+        })
+        .collect()
+}
+
+/// Parses a range given as `start..end`, or in YAML format such as `{ start: 1000, end: 2000 }`.
+///
+/// Both ends are parsed by [`from_str`](std::str::FromStr::from_str). A range whose start is greater than its end is an error.
+///
+/// ```
+/// assert_eq!(econf::parse::range::<u16>("1000..2000"), Ok(1000..2000));
+/// assert_eq!(econf::parse::range::<u16>("{ start: 1000, end: 2000 }"), Ok(1000..2000));
+/// assert!(econf::parse::range::<u16>("2000..1000").is_err());
+/// assert!(econf::parse::range::<u16>("1000..=2000").is_err());
+/// ```
+///
+pub fn range<T>(s: &str) -> Result<Range<T>, String>
+where
+    T: FromStr + DeserializeOwned + PartialOrd,
+    T::Err: Display,
+{
+    let (start, end) = match s.split_once("..") {
+        Some((_, end)) if end.starts_with('=') => {
+            return Err(format!("expected `start..end`, found \"{}\"", s))
+        }
+        Some((start, end)) => (range_end(start)?, range_end(end)?),
+        None => {
+            let range: Range<T> = serde_yaml::from_str(s).map_err(|e| e.to_string())?;
+            (range.start, range.end)
+        }
+    };
+    if start > end {
+        return Err(format!("start is greater than end in \"{}\"", s));
+    }
+    Ok(start..end)
+}
+
+/// Parses an inclusive range given as `start..=end`, or in YAML format such as `{ start: 1000, end: 2000 }`.
+///
+/// Both ends are parsed by [`from_str`](std::str::FromStr::from_str). A range whose start is greater than its end is an error.
+///
+/// ```
+/// assert_eq!(econf::parse::range_inclusive::<u16>("1000..=2000"), Ok(1000..=2000));
+/// assert_eq!(econf::parse::range_inclusive::<u16>("{ start: 1000, end: 2000 }"), Ok(1000..=2000));
+/// assert!(econf::parse::range_inclusive::<u16>("2000..=1000").is_err());
+/// assert!(econf::parse::range_inclusive::<u16>("1000..2000").is_err());
+/// ```
+///
+pub fn range_inclusive<T>(s: &str) -> Result<RangeInclusive<T>, String>
+where
+    T: FromStr + DeserializeOwned + PartialOrd,
+    T::Err: Display,
+{
+    let (start, end) = match s.split_once("..") {
+        Some((start, end)) => match end.strip_prefix('=') {
+            Some(end) => (range_end(start)?, range_end(end)?),
+            None => return Err(format!("expected `start..=end`, found \"{}\"", s)),
+        },
+        None => serde_yaml::from_str::<RangeInclusive<T>>(s)
+            .map_err(|e| e.to_string())?
+            .into_inner(),
+    };
+    if start > end {
+        return Err(format!("start is greater than end in \"{}\"", s));
+    }
+    Ok(start..=end)
+}
+
+/// Parses an end of a range.
+fn range_end<T>(s: &str) -> Result<T, String>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    let s = s.trim();
+    s.parse().map_err(|e| format!("\"{}\": {}", s, e))
+}
+
+/// Parses an enum in YAML format.
+///
+/// In addition to the `!Variant` tag syntax of [`serde_yaml`], a map with a single entry such as
+/// `{ Variant: value }` is accepted as the variant with its data. Used for enums with data.
+///
+/// ```
+/// #[derive(Debug, PartialEq, serde::Deserialize)]
+/// enum Backend {
+///     Local,
+///     Remote { host: String, port: u16 },
+/// }
+///
+/// let remote = Backend::Remote { host: "x".into(), port: 9 };
+/// assert_eq!(econf::parse::yaml_enum::<Backend>("{ Remote: { host: x, port: 9 } }").unwrap(), remote);
+/// assert_eq!(econf::parse::yaml_enum::<Backend>("!Remote { host: x, port: 9 }").unwrap(), remote);
+/// assert_eq!(econf::parse::yaml_enum::<Backend>("Local").unwrap(), Backend::Local);
+/// ```
+///
+pub fn yaml_enum<T>(s: &str) -> Result<T, serde_yaml::Error>
+where
+    T: DeserializeOwned,
+{
+    let mut value: Value = serde_yaml::from_str(s)?;
+    if let Value::Mapping(map) = &value {
+        if let (1, Some((Value::String(tag), data))) = (map.len(), map.iter().next()) {
+            value = Value::Tagged(Box::new(TaggedValue {
+                tag: Tag::new(tag),
+                value: data.clone(),
+            }));
+        }
+    }
+    serde_yaml::from_value(value)
+}
@@ -0,0 +1,169 @@
+use std::fmt::{self, Display};
+
+use crate::LoadError;
+
+/// The outcome of looking up an environment variable.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoadRecord {
+    /// The name of the environment variable.
+    pub name: String,
+    /// Whether the environment variable was found and parsed.
+    pub status: LoadStatus,
+}
+
+/// Whether an environment variable was found and parsed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoadStatus {
+    /// Found and parsed. The value is loaded.
+    Loaded,
+    /// Not found. The original value is kept.
+    NotFound,
+    /// Not found. The value given by `#[econf(default = "...")]` is loaded.
+    Defaulted,
+    /// Found but failed to parse. The original value is kept.
+    Failed,
+}
+
+/// A summary of loading, returned by [`load_with_report`](crate::load_with_report).
+///
+/// The [`Display`] implementation gives a human-readable summary: the first line counts the environment variables
+/// loaded, not found, failed and ambiguous. The following lines list the loaded ones, the defaulted ones, the errors and the ambiguous ones, if any.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Report {
+    records: Vec<LoadRecord>,
+    errors: Vec<LoadError>,
+    subtrees: Vec<(String, bool)>,
+}
+
+impl Report {
+    pub(crate) fn new(
+        records: Vec<LoadRecord>,
+        errors: Vec<LoadError>,
+        subtrees: Vec<(String, bool)>,
+    ) -> Self {
+        Self {
+            records,
+            errors,
+            subtrees,
+        }
+    }
+
+    /// The environment variables looked up, in order.
+    pub fn records(&self) -> &[LoadRecord] {
+        &self.records
+    }
+
+    /// The errors that occurred.
+    pub fn errors(&self) -> &[LoadError] {
+        &self.errors
+    }
+
+    /// The number of environment variables loaded, i.e. the values overridden by the environment.
+    ///
+    /// ```
+    /// # use econf::LoadEnv;
+    /// #[derive(LoadEnv)]
+    /// struct A {
+    ///     x: u32,
+    ///     y: u32,
+    /// }
+    ///
+    /// std::env::set_var("LOADED_COUNT_X", "2");
+    ///
+    /// let (_, report) = econf::load_with_report(A { x: 1, y: 1 }, "LOADED_COUNT");
+    /// if report.loaded_count() > 0 {
+    ///     println!("configuration overridden by environment");
+    /// }
+    /// assert_eq!(report.loaded_count(), 1);
+    /// ```
+    ///
+    pub fn loaded_count(&self) -> usize {
+        self.records
+            .iter()
+            .filter(|r| r.status == LoadStatus::Loaded)
+            .count()
+    }
+
+    /// The paths of the structs, including nested ones, with no environment variable loaded under them, in order.
+    ///
+    /// A nested struct listed here runs on its original values only, e.g. a subsystem left with its defaults.
+    ///
+    /// ```
+    /// # use econf::LoadEnv;
+    /// #[derive(LoadEnv)]
+    /// struct A {
+    ///     cache: Cache,
+    ///     db: Db,
+    /// }
+    ///
+    /// #[derive(LoadEnv)]
+    /// struct Cache {
+    ///     size: u32,
+    /// }
+    ///
+    /// #[derive(LoadEnv)]
+    /// struct Db {
+    ///     port: u16,
+    /// }
+    ///
+    /// std::env::set_var("UNTOUCHED_DB_PORT", "5433");
+    ///
+    /// let a = A { cache: Cache { size: 64 }, db: Db { port: 5432 } };
+    /// let (_, report) = econf::load_with_report(a, "UNTOUCHED");
+    /// assert_eq!(report.untouched(), ["UNTOUCHED_CACHE"]);
+    /// ```
+    ///
+    pub fn untouched(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = vec![];
+        for (name, touched) in &self.subtrees {
+            // A flattened struct has the same path as its parent.
+            if !touched && !names.contains(&name.as_str()) {
+                names.push(name);
+            }
+        }
+        names
+    }
+
+    fn names(&self, status: LoadStatus) -> Vec<&str> {
+        self.records
+            .iter()
+            .filter(|r| r.status == status)
+            .map(|r| r.name.as_str())
+            .collect()
+    }
+}
+
+impl Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let loaded = self.names(LoadStatus::Loaded);
+        let not_found = self.names(LoadStatus::NotFound);
+        let defaulted = self.names(LoadStatus::Defaulted);
+        let (duplicates, errors): (Vec<_>, Vec<_>) = self
+            .errors
+            .iter()
+            .partition(|e| matches!(e, LoadError::Duplicate { .. }));
+
+        write!(
+            f,
+            "econf: {} loaded, {} not found, {} errors, {} duplicates",
+            loaded.len(),
+            not_found.len(),
+            errors.len(),
+            duplicates.len()
+        )?;
+        if !loaded.is_empty() {
+            write!(f, "\n  loaded: {}", loaded.join(", "))?;
+        }
+        if !defaulted.is_empty() {
+            write!(f, "\n  defaulted: {}", defaulted.join(", "))?;
+        }
+        for e in errors {
+            write!(f, "\n  error: {}", e)?;
+        }
+        if !duplicates.is_empty() {
+            let names: Vec<_> = duplicates.iter().map(|e| e.name()).collect();
+            write!(f, "\n  duplicates: {}", names.join(", "))?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,217 @@
+use log::warn;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use std::ffi::OsString;
+use std::rc::Rc;
+
+/// A source of environment variables.
+///
+/// [`Loader`](crate::Loader) reads the process environment ([`Env`]) by default.
+/// Another source such as a `HashMap` parsed from a `.env` file can be given by [`Loader::with_source`](crate::Loader::with_source).
+///
+/// ```
+/// # use econf::{LoadEnv, Loader};
+/// # use std::collections::HashMap;
+/// #[derive(LoadEnv)]
+/// struct A {
+///     x: bool,
+///     y: u64,
+/// }
+///
+/// let source = HashMap::from([("SOURCE_Y".to_owned(), "2".to_owned())]);
+/// let mut loader = Loader::with_source(source);
+///
+/// let a = A { x: true, y: 1 }.load("SOURCE", &mut loader);
+/// assert_eq!(a.y, 2);
+/// ```
+///
+pub trait EnvSource {
+    /// Returns the value of the environment variable `name`, if any.
+    fn var(&self, name: &str) -> Option<String>;
+
+    /// Returns the value of the environment variable `name` as is, even if it is not valid UTF-8.
+    ///
+    /// Used to load `OsString` and `PathBuf`. Defaults to [`var`](Self::var).
+    fn var_os(&self, name: &str) -> Option<OsString> {
+        self.var(name).map(OsString::from)
+    }
+
+    /// Returns all the environment variables.
+    fn vars(&self) -> Vec<(String, String)>;
+}
+
+thread_local! {
+    static DEFAULT_SOURCE: RefCell<Option<Rc<dyn EnvSource>>> = const { RefCell::new(None) };
+}
+
+/// Makes [`Loader::new`](crate::Loader::new), and so [`load`](crate::load) and the other functions, read `source`
+/// instead of the process environment on the current thread.
+///
+/// This is meant for tests that use the plain [`load`](crate::load) without passing a source around.
+/// The source is thread-local: other threads, including the ones spawned afterwards, still read the process environment.
+/// Since the test harness runs each test on its own thread, tests setting different sources don't interfere,
+/// but a test running code on other threads needs to set the source there too. Reset by [`reset_default_source`].
+///
+/// ```
+/// # use econf::LoadEnv;
+/// # use std::collections::HashMap;
+/// #[derive(LoadEnv)]
+/// struct A {
+///     port: u16,
+/// }
+///
+/// econf::set_default_source(HashMap::from([("DEFAULT_PORT".to_owned(), "80".to_owned())]));
+/// assert_eq!(econf::load(A { port: 8080 }, "DEFAULT").port, 80);
+///
+/// econf::reset_default_source();
+/// assert_eq!(econf::load(A { port: 8080 }, "DEFAULT").port, 8080);
+/// ```
+///
+pub fn set_default_source<S>(source: S)
+where
+    S: EnvSource + 'static,
+{
+    DEFAULT_SOURCE.with(|s| *s.borrow_mut() = Some(Rc::new(source)));
+}
+
+/// Makes [`Loader::new`](crate::Loader::new) read the process environment again on the current thread.
+///
+/// See [`set_default_source`].
+pub fn reset_default_source() {
+    DEFAULT_SOURCE.with(|s| *s.borrow_mut() = None);
+}
+
+/// The source set by [`set_default_source`], or the process environment.
+pub(crate) fn default_source() -> Rc<dyn EnvSource> {
+    DEFAULT_SOURCE.with(|s| s.borrow().clone().unwrap_or_else(|| Rc::new(Env)))
+}
+
+/// The environment of the current process.
+///
+/// If built with the `disabled` feature, the environment is never read and looks empty.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Env;
+
+#[cfg(not(feature = "disabled"))]
+impl EnvSource for Env {
+    fn var(&self, name: &str) -> Option<String> {
+        std::env::var(name).ok()
+    }
+
+    fn var_os(&self, name: &str) -> Option<OsString> {
+        std::env::var_os(name)
+    }
+
+    fn vars(&self) -> Vec<(String, String)> {
+        // `std::env::vars` panics on a name or value that is not valid UTF-8, which can't be loaded anyway.
+        std::env::vars_os()
+            .filter_map(|(name, value)| Some((name.into_string().ok()?, value.into_string().ok()?)))
+            .collect()
+    }
+}
+
+#[cfg(feature = "disabled")]
+impl EnvSource for Env {
+    fn var(&self, _: &str) -> Option<String> {
+        None
+    }
+
+    fn vars(&self) -> Vec<(String, String)> {
+        vec![]
+    }
+}
+
+impl EnvSource for HashMap<String, String> {
+    fn var(&self, name: &str) -> Option<String> {
+        self.get(name).cloned()
+    }
+
+    fn vars(&self) -> Vec<(String, String)> {
+        self.iter().map(|(k, v)| (k.clone(), v.clone())).collect()
+    }
+}
+
+impl EnvSource for BTreeMap<String, String> {
+    fn var(&self, name: &str) -> Option<String> {
+        self.get(name).cloned()
+    }
+
+    fn vars(&self) -> Vec<(String, String)> {
+        self.iter().map(|(k, v)| (k.clone(), v.clone())).collect()
+    }
+}
+
+/// Variables given by command-line arguments in the form of `--set KEY=VALUE`.
+///
+/// Both `--set KEY=VALUE` and `--set=KEY=VALUE` are accepted. Other arguments are ignored,
+/// and the last one wins if the same key is given more than once.
+///
+/// ```
+/// # use econf::{ArgsSource, EnvSource};
+/// let source = ArgsSource::new(["app", "--set", "APP_PORT=80", "-v", "--set=APP_HOST=a=b"]);
+///
+/// assert_eq!(source.var("APP_PORT").as_deref(), Some("80"));
+/// assert_eq!(source.var("APP_HOST").as_deref(), Some("a=b"));
+/// ```
+///
+#[derive(Debug, Clone, Default)]
+pub struct ArgsSource {
+    vars: BTreeMap<String, String>,
+}
+
+impl ArgsSource {
+    /// Parses the given arguments.
+    pub fn new<I, S>(args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut vars = BTreeMap::new();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let arg = arg.as_ref();
+            let pair = match arg.strip_prefix("--set") {
+                Some("") => match args.next() {
+                    Some(pair) => pair.as_ref().to_owned(),
+                    None => break,
+                },
+                Some(rest) => match rest.strip_prefix('=') {
+                    Some(pair) => pair.to_owned(),
+                    None => continue,
+                },
+                None => continue,
+            };
+            if let Some((k, v)) = pair.split_once('=') {
+                vars.insert(k.to_owned(), v.to_owned());
+            }
+        }
+        Self { vars }
+    }
+
+    /// Parses the command-line arguments of the current process.
+    ///
+    /// Arguments that are not valid UTF-8 are logged and ignored.
+    pub fn from_args() -> Self {
+        Self::new(
+            std::env::args_os()
+                .skip(1)
+                .filter_map(|arg| match arg.into_string() {
+                    Ok(arg) => Some(arg),
+                    Err(arg) => {
+                        warn!("econf: argument {:?} is not valid UTF-8, ignored", arg);
+                        None
+                    }
+                }),
+        )
+    }
+}
+
+impl EnvSource for ArgsSource {
+    fn var(&self, name: &str) -> Option<String> {
+        self.vars.var(name)
+    }
+
+    fn vars(&self) -> Vec<(String, String)> {
+        self.vars.vars()
+    }
+}
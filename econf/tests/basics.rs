@@ -15,8 +15,8 @@ fn boolean() {
 
     let a = Boolean { a: false, b: false };
     let a = econf::load(a, "bool");
-    assert_eq!(a.a, false);
-    assert_eq!(a.b, true);
+    assert!(!a.a);
+    assert!(a.b);
 }
 
 #[derive(LoadEnv)]
@@ -200,6 +200,66 @@ fn maps() {
     assert_eq!(a.m6, m6);
 }
 
+#[derive(Debug, PartialEq, Eq, Hash, PartialOrd, Ord, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum KeyLevel {
+    Low,
+    High,
+}
+
+#[derive(Default, LoadEnv)]
+struct EnumKeys {
+    m1: HashMap<KeyLevel, u32>,
+    m2: BTreeMap<KeyLevel, String>,
+    m3: HashMap<KeyLevel, u32>,
+}
+
+#[test]
+fn enum_keys() {
+    use econf::Loader;
+
+    std::env::set_var("ENUM_KEYS_M1", "{ low: 1, high: 2 }");
+    std::env::set_var("ENUM_KEYS_M2", "{ high: a }");
+    std::env::set_var("ENUM_KEYS_M3", "{ medium: 1 }");
+
+    let mut loader = Loader::new();
+    let a = EnumKeys::default().load("enum_keys", &mut loader);
+    assert_eq!(
+        a.m1,
+        HashMap::from([(KeyLevel::Low, 1), (KeyLevel::High, 2)])
+    );
+    assert_eq!(a.m2, BTreeMap::from([(KeyLevel::High, "a".into())]));
+    assert!(a.m3.is_empty());
+    assert_eq!(loader.errors()[0].name(), "ENUM_KEYS_M3");
+}
+
+#[derive(Default, LoadEnv)]
+struct JsonValues {
+    m1: HashMap<String, serde_json::Value>,
+    m2: HashMap<String, serde_json::Value>,
+}
+
+#[test]
+fn json_values() {
+    use serde_json::json;
+
+    std::env::set_var("JSON_VALUES_M1", "{a: 1, b: {c: 2}}");
+    std::env::set_var("JSON_VALUES_M2", r#"{"x": [true, null, "s"], "y": 1.5}"#);
+
+    let a = econf::load(JsonValues::default(), "json_values");
+    assert_eq!(
+        a.m1,
+        HashMap::from([("a".into(), json!(1)), ("b".into(), json!({"c": 2}))])
+    );
+    assert_eq!(
+        a.m2,
+        HashMap::from([
+            ("x".into(), json!([true, null, "s"])),
+            ("y".into(), json!(1.5))
+        ])
+    );
+}
+
 #[derive(LoadEnv)]
 struct Chars {
     s1: String,
@@ -291,6 +351,214 @@ fn tuples() {
     assert_eq!(a.t3, (vec![9, 8, 8], -99.9));
 }
 
+type Tuple13 = (u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, String);
+type Tuple16 = (
+    u8,
+    u8,
+    u8,
+    u8,
+    u8,
+    u8,
+    u8,
+    u8,
+    u8,
+    u8,
+    u8,
+    u8,
+    u8,
+    u8,
+    u8,
+    u8,
+);
+
+#[derive(LoadEnv)]
+struct LongTuples {
+    t1: Tuple13,
+    t2: Tuple16,
+}
+
+#[derive(LoadEnv)]
+struct Required {
+    #[econf(required)]
+    v1: String,
+    #[econf(required)]
+    v2: u32,
+    #[econf(required)]
+    v3: u32,
+    #[econf(required)]
+    v4: Option<String>,
+    #[econf(required)]
+    v5: RequiredNested,
+    #[econf(required)]
+    v6: Vec<String>,
+}
+
+#[derive(Default, LoadEnv)]
+struct RequiredNested {
+    a: bool,
+    b: bool,
+}
+
+#[test]
+fn required() {
+    use econf::LoadError;
+
+    std::env::set_var("REQUIRED_V1", "postgres://localhost");
+    std::env::set_var("REQUIRED_V3", "x");
+    std::env::set_var("REQUIRED_V4", "null");
+    std::env::set_var("REQUIRED_V5_B", "true");
+
+    let a = Required {
+        v1: String::new(),
+        v2: 1,
+        v3: 1,
+        v4: Some("a".into()),
+        v5: RequiredNested::default(),
+        v6: vec![],
+    };
+
+    let Err(e) = econf::try_load(a, "required") else {
+        panic!("required fields are missing");
+    };
+    assert_eq!(
+        e.errors(),
+        [
+            LoadError::NotFound {
+                name: "REQUIRED_V2".into()
+            },
+            LoadError::Parse {
+                name: "REQUIRED_V3".into(),
+                value: "x".into(),
+                msg: "invalid digit found in string".into(),
+            },
+            LoadError::NotFound {
+                name: "REQUIRED_V6".into()
+            },
+        ]
+    );
+}
+
+#[derive(Debug, LoadEnv)]
+#[econf(validate = "check_bounds")]
+struct Bounds {
+    min: u32,
+    max: u32,
+}
+
+fn check_bounds(b: &Bounds) -> Result<(), String> {
+    if b.min <= b.max {
+        Ok(())
+    } else {
+        Err(format!("min {} exceeds max {}", b.min, b.max))
+    }
+}
+
+#[derive(Debug, LoadEnv)]
+struct Validated {
+    b1: Bounds,
+    b2: Bounds,
+}
+
+#[test]
+fn validated() {
+    use econf::LoadError;
+
+    std::env::set_var("VALIDATED_B1_MAX", "5");
+    std::env::set_var("VALIDATED_B2_MIN", "20");
+
+    let bounds = || Bounds { min: 1, max: 10 };
+    let a = Validated {
+        b1: bounds(),
+        b2: bounds(),
+    };
+
+    let (a, report) = econf::load_with_report(a, "validated");
+    assert_eq!(a.b1.max, 5);
+    assert_eq!(a.b2.min, 20);
+    assert_eq!(
+        report.errors(),
+        [LoadError::Validation {
+            name: "VALIDATED_B2".into(),
+            msg: "min 20 exceeds max 10".into(),
+        }]
+    );
+}
+
+#[derive(LoadEnv)]
+struct NonzeroDurations {
+    #[econf(nonzero)]
+    d1: Duration,
+    #[econf(nonzero)]
+    d2: Duration,
+    #[econf(nonzero, duration_float_secs)]
+    d3: Duration,
+    #[econf(nonzero, duration_float_secs)]
+    d4: Duration,
+    d5: Duration,
+}
+
+#[test]
+fn nonzero_durations() {
+    use econf::{LoadError, Loader};
+
+    std::env::set_var("NONZERO_DURATIONS_D1", "0s");
+    std::env::set_var("NONZERO_DURATIONS_D2", "5s");
+    std::env::set_var("NONZERO_DURATIONS_D3", "0.0");
+    std::env::set_var("NONZERO_DURATIONS_D4", "1.5");
+    std::env::set_var("NONZERO_DURATIONS_D5", "0");
+
+    let a = NonzeroDurations {
+        d1: Duration::from_secs(1),
+        d2: Duration::from_secs(1),
+        d3: Duration::from_secs(1),
+        d4: Duration::from_secs(1),
+        d5: Duration::from_secs(1),
+    };
+
+    let mut loader = Loader::new();
+    let a = a.load("nonzero_durations", &mut loader);
+    assert_eq!(a.d1, Duration::from_secs(1));
+    assert_eq!(a.d2, Duration::from_secs(5));
+    assert_eq!(a.d3, Duration::from_secs(1));
+    assert_eq!(a.d4, Duration::from_millis(1500));
+    assert_eq!(a.d5, Duration::ZERO);
+    assert_eq!(
+        loader.errors(),
+        [
+            LoadError::Validation {
+                name: "NONZERO_DURATIONS_D1".into(),
+                msg: "must not be zero".into(),
+            },
+            LoadError::Validation {
+                name: "NONZERO_DURATIONS_D3".into(),
+                msg: "must not be zero".into(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn long_tuples() {
+    std::env::set_var(
+        "LONG_TUPLES_T1",
+        "[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, thirteen]",
+    );
+    std::env::set_var(
+        "LONG_TUPLES_T2",
+        "[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 16]",
+    );
+
+    let a = LongTuples {
+        t1: (0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, String::new()),
+        t2: (0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0),
+    };
+    let a = econf::load(a, "long_tuples");
+    assert_eq!(a.t1.0, 1);
+    assert_eq!(a.t1.11, 12);
+    assert_eq!(a.t1.12, "thirteen");
+    assert_eq!(a.t2.15, 16);
+}
+
 #[derive(LoadEnv, PartialEq, Debug)]
 struct TS1(String, u32, char);
 
@@ -351,7 +619,7 @@ fn skipped() {
     };
 
     let a = econf::load(a, "skipped");
-    assert_eq!(a.v1, true);
+    assert!(a.v1);
     assert_eq!(a.v2, 42);
     assert_eq!(a.v3.s, "initial".to_string());
 }
@@ -385,7 +653,7 @@ fn renamed() {
     };
 
     let a = econf::load(a, "renamed");
-    assert_eq!(a.v1, true);
+    assert!(a.v1);
     assert_eq!(a.v2, 42);
     assert_eq!(a.v3.s, "renamed text".to_string());
 }
@@ -531,7 +799,7 @@ fn non_zero_numbers() {
     assert_eq!(a.u5.get(), 111132897323);
 }
 
-use std::time::Duration;
+use std::time::{Duration, SystemTime};
 
 #[derive(LoadEnv)]
 struct Durations {
@@ -557,6 +825,90 @@ fn duration() {
     assert_eq!(a.d3, Duration::from_secs(3600));
 }
 
+#[derive(LoadEnv)]
+struct CombinedDurations {
+    d1: Duration,
+    d2: Duration,
+    d3: Duration,
+    d4: Duration,
+    d5: Duration,
+}
+
+#[test]
+fn combined_duration() {
+    std::env::set_var("COMBINED_DURATIONS_D1", "1h30m");
+    std::env::set_var("COMBINED_DURATIONS_D2", "500ms");
+    std::env::set_var("COMBINED_DURATIONS_D3", "1s500ms");
+    std::env::set_var("COMBINED_DURATIONS_D4", "1h 30m 15s");
+    std::env::set_var("COMBINED_DURATIONS_D5", "250us");
+
+    let a = CombinedDurations {
+        d1: Duration::ZERO,
+        d2: Duration::ZERO,
+        d3: Duration::ZERO,
+        d4: Duration::ZERO,
+        d5: Duration::ZERO,
+    };
+
+    let a = econf::load(a, "combined_durations");
+    assert_eq!(a.d1, Duration::from_secs(5400));
+    assert_eq!(a.d2, Duration::from_millis(500));
+    assert_eq!(a.d3, Duration::from_millis(1500));
+    assert_eq!(a.d4, Duration::from_secs(5415));
+    assert_eq!(a.d5, Duration::from_micros(250));
+}
+
+#[derive(LoadEnv)]
+struct ZeroDurations {
+    d1: Duration,
+    d2: Duration,
+    d3: Duration,
+}
+
+#[test]
+fn zero_duration() {
+    std::env::set_var("ZERO_DURATIONS_D1", "0");
+    std::env::set_var("ZERO_DURATIONS_D2", "0s");
+    std::env::set_var("ZERO_DURATIONS_D3", " 0 ");
+
+    let a = ZeroDurations {
+        d1: Duration::from_secs(30),
+        d2: Duration::from_secs(30),
+        d3: Duration::from_secs(30),
+    };
+
+    let a = econf::load(a, "zero_durations");
+    assert_eq!(a.d1, Duration::ZERO);
+    assert_eq!(a.d2, Duration::ZERO);
+    assert_eq!(a.d3, Duration::ZERO);
+}
+
+#[derive(LoadEnv)]
+struct SystemTimes {
+    t1: SystemTime,
+    t2: SystemTime,
+    t3: SystemTime,
+}
+
+#[test]
+fn system_time() {
+    std::env::set_var("SYSTEM_TIMES_T1", "2024-06-01T00:00:00Z");
+    std::env::set_var("SYSTEM_TIMES_T2", "2024-06-01T00:00:00.5Z");
+    std::env::set_var("SYSTEM_TIMES_T3", "yesterday");
+
+    let a = SystemTimes {
+        t1: SystemTime::UNIX_EPOCH,
+        t2: SystemTime::UNIX_EPOCH,
+        t3: SystemTime::UNIX_EPOCH,
+    };
+
+    let a = econf::load(a, "system_times");
+    let start = SystemTime::UNIX_EPOCH + Duration::from_secs(1717200000);
+    assert_eq!(a.t1, start);
+    assert_eq!(a.t2, start + Duration::from_millis(500));
+    assert_eq!(a.t3, SystemTime::UNIX_EPOCH);
+}
+
 #[derive(LoadEnv)]
 struct Paths {
     p1: PathBuf,
@@ -581,6 +933,35 @@ fn path_buf() {
     assert_eq!(a.p3, "data.db".parse::<PathBuf>().unwrap());
 }
 
+#[derive(LoadEnv)]
+struct OsPaths {
+    path: PathBuf,
+    name: std::ffi::OsString,
+}
+
+#[cfg(unix)]
+#[test]
+fn non_utf8_path() {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+
+    let raw = OsStr::from_bytes(b"/tmp/caf\xe9");
+    std::env::set_var("OS_PATHS_PATH", raw);
+    std::env::set_var("OS_PATHS_NAME", raw);
+
+    let a = OsPaths {
+        path: PathBuf::new(),
+        name: "a".into(),
+    };
+    let (a, report) = econf::load_with_report(a, "os_paths");
+    assert_eq!(a.path.as_os_str().as_bytes(), b"/tmp/caf\xe9");
+    assert_eq!(a.name, raw);
+    assert_eq!(report.loaded_count(), 2);
+
+    // Listing the environment skips the values that are not valid UTF-8.
+    assert!(econf::Loader::new().unused_vars("os_paths").is_empty());
+}
+
 #[test]
 fn generics() {
     std::env::set_var("GENERICS_A", "33");
@@ -603,3 +984,2836 @@ fn generics() {
     assert_eq!(g.a, 33);
     assert_eq!(g.b, "akeome".to_string());
 }
+
+#[derive(LoadEnv)]
+struct TrailingSeparator {
+    v1: u32,
+    v2: Chars,
+}
+
+#[test]
+fn trailing_separator() {
+    std::env::set_var("TRAILING_V1", "5");
+    std::env::set_var("TRAILING_V2_S1", "Hello");
+
+    let a = TrailingSeparator {
+        v1: 0,
+        v2: Chars {
+            s1: "Gomi".into(),
+            s2: "Kami".into(),
+            s3: "Semi".into(),
+            s4: 'p',
+            s5: 'q',
+        },
+    };
+    let a = econf::load(a, "trailing_");
+    assert_eq!(a.v1, 5);
+    assert_eq!(a.v2.s1, "Hello");
+    assert_eq!(a.v2.s2, "Kami");
+}
+
+#[derive(LoadEnv)]
+struct FloatSecs {
+    #[econf(duration_float_secs)]
+    d1: Duration,
+    #[econf(duration_float_secs)]
+    d2: Duration,
+    #[econf(duration_float_secs)]
+    d3: Duration,
+    #[econf(duration_float_secs)]
+    d4: Duration,
+}
+
+#[test]
+fn duration_float_secs() {
+    std::env::set_var("FLOAT_SECS_D1", "1.5");
+    std::env::set_var("FLOAT_SECS_D2", "0.25");
+    std::env::set_var("FLOAT_SECS_D3", "1m");
+
+    let a = FloatSecs {
+        d1: Duration::from_secs(100),
+        d2: Duration::from_secs(100),
+        d3: Duration::from_secs(100),
+        d4: Duration::from_secs(100),
+    };
+
+    let a = econf::load(a, "float_secs");
+    assert_eq!(a.d1, Duration::from_millis(1500));
+    assert_eq!(a.d2, Duration::from_millis(250));
+    assert_eq!(a.d3, Duration::from_secs(60));
+    assert_eq!(a.d4, Duration::from_secs(100));
+}
+
+#[derive(LoadEnv)]
+struct Atomics {
+    flag: std::sync::atomic::AtomicBool,
+    count: std::sync::atomic::AtomicU64,
+    offset: std::sync::atomic::AtomicIsize,
+    limit: std::sync::atomic::AtomicU32,
+}
+
+#[test]
+fn atomics() {
+    use std::sync::atomic::{AtomicBool, AtomicIsize, AtomicU32, AtomicU64, Ordering};
+
+    std::env::set_var("ATOMICS_FLAG", "true");
+    std::env::set_var("ATOMICS_COUNT", "42");
+    std::env::set_var("ATOMICS_OFFSET", "-3");
+    std::env::set_var("ATOMICS_LIMIT", "x");
+
+    let a = Atomics {
+        flag: AtomicBool::new(false),
+        count: AtomicU64::new(1),
+        offset: AtomicIsize::new(1),
+        limit: AtomicU32::new(1),
+    };
+
+    let a = econf::load(a, "atomics");
+    // `LoadEnv::load` takes precedence over the inherent `load` in method calls.
+    assert!(AtomicBool::load(&a.flag, Ordering::Relaxed));
+    assert_eq!(AtomicU64::load(&a.count, Ordering::Relaxed), 42);
+    assert_eq!(AtomicIsize::load(&a.offset, Ordering::Relaxed), -3);
+    assert_eq!(AtomicU32::load(&a.limit, Ordering::Relaxed), 1);
+}
+
+#[derive(Debug, PartialEq, serde::Deserialize)]
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+#[derive(LoadEnv)]
+struct Yaml {
+    #[econf(yaml)]
+    p1: Point,
+    #[econf(yaml)]
+    p2: Point,
+    #[econf(yaml)]
+    p3: Point,
+    #[econf(yaml)]
+    port: u16,
+}
+
+#[test]
+fn yaml() {
+    std::env::set_var("YAML_P1", "{x: 1, y: 2}");
+    std::env::set_var("YAML_P2", "{x: 1}");
+    std::env::set_var("YAML_PORT", "8080");
+
+    let origin = || Point { x: 0, y: 0 };
+    let a = Yaml {
+        p1: origin(),
+        p2: origin(),
+        p3: origin(),
+        port: 80,
+    };
+
+    let a = econf::load(a, "yaml");
+    assert_eq!(a.p1, Point { x: 1, y: 2 });
+    assert_eq!(a.p2, origin());
+    assert_eq!(a.p3, origin());
+    assert_eq!(a.port, 8080);
+}
+
+#[derive(LoadEnv)]
+struct SerdeDuration {
+    #[econf(serde_duration)]
+    d1: Duration,
+    #[econf(serde_duration)]
+    d2: Duration,
+    #[econf(serde_duration)]
+    d3: Duration,
+}
+
+#[test]
+fn serde_duration() {
+    std::env::set_var("SERDE_DURATION_D1", "{secs: 60, nanos: 0}");
+    std::env::set_var("SERDE_DURATION_D2", r#"{"secs": 1, "nanos": 500000000}"#);
+    std::env::set_var("SERDE_DURATION_D3", "1m");
+
+    let a = SerdeDuration {
+        d1: Duration::from_secs(100),
+        d2: Duration::from_secs(100),
+        d3: Duration::from_secs(100),
+    };
+
+    let a = econf::load(a, "serde_duration");
+    assert_eq!(a.d1, Duration::from_secs(60));
+    assert_eq!(a.d2, Duration::from_millis(1500));
+    assert_eq!(a.d3, Duration::from_secs(100));
+}
+
+#[derive(LoadEnv)]
+#[allow(non_snake_case)]
+struct Verbatim {
+    v1: u32,
+    #[econf(verbatim)]
+    mixedCase: u32,
+    #[econf(verbatim)]
+    nested: VerbatimNested,
+}
+
+#[derive(LoadEnv)]
+#[econf(verbatim)]
+#[allow(non_snake_case)]
+struct VerbatimNested {
+    innerValue: String,
+}
+
+#[test]
+#[allow(non_snake_case)]
+fn verbatim() {
+    std::env::set_var("VERBATIM_V1", "1");
+    std::env::set_var("VERBATIM_mixedCase", "2");
+    std::env::set_var("VERBATIM_MIXEDCASE", "3");
+    std::env::set_var("VERBATIM_nested_innerValue", "verbatim");
+
+    let a = Verbatim {
+        v1: 0,
+        mixedCase: 0,
+        nested: VerbatimNested {
+            innerValue: "initial".into(),
+        },
+    };
+
+    let a = econf::load(a, "VERBATIM");
+    assert_eq!(a.v1, 1);
+    assert_eq!(a.mixedCase, 2);
+    assert_eq!(a.nested.innerValue, "verbatim");
+}
+
+#[derive(LoadEnv)]
+struct Delimited {
+    #[econf(delimiter)]
+    d1: VecDeque<char>,
+    #[econf(delimiter = ";")]
+    d2: Vec<u32>,
+    #[econf(delimiter)]
+    d3: VecDeque<u32>,
+}
+
+#[test]
+fn delimited() {
+    std::env::set_var("DELIMITED_D1", "a,b,c");
+    std::env::set_var("DELIMITED_D2", "3;2;1");
+    std::env::set_var("DELIMITED_D3", "1,x");
+
+    let a = Delimited {
+        d1: VecDeque::new(),
+        d2: vec![],
+        d3: VecDeque::from([9]),
+    };
+
+    let a = econf::load(a, "delimited");
+    assert_eq!(a.d1, VecDeque::from(['a', 'b', 'c']));
+    assert_eq!(a.d2, vec![3, 2, 1]);
+    assert_eq!(a.d3, VecDeque::from([9]));
+}
+
+#[derive(LoadEnv)]
+struct DelimitedTrimmed {
+    #[econf(delimiter)]
+    d1: Vec<String>,
+    #[econf(delimiter)]
+    d2: HashSet<String>,
+    #[econf(delimiter = ";", kv_sep = "=")]
+    d3: BTreeMap<String, u32>,
+    #[econf(delimiter)]
+    d4: Vec<u32>,
+}
+
+#[test]
+fn delimited_trimmed() {
+    std::env::set_var("DELIMITED_TRIMMED_D1", " a, b ,c ");
+    std::env::set_var("DELIMITED_TRIMMED_D2", "");
+    std::env::set_var("DELIMITED_TRIMMED_D3", "a = 1; b=2 ");
+    std::env::set_var("DELIMITED_TRIMMED_D4", "1,,2");
+
+    let a = DelimitedTrimmed {
+        d1: vec![],
+        d2: HashSet::from(["x".to_owned()]),
+        d3: BTreeMap::new(),
+        d4: vec![9],
+    };
+
+    let a = econf::load(a, "delimited_trimmed");
+    assert_eq!(a.d1, ["a", "b", "c"]);
+    assert!(a.d2.is_empty());
+    assert_eq!(a.d3, BTreeMap::from([("a".into(), 1), ("b".into(), 2)]));
+    assert_eq!(a.d4, [9]);
+}
+
+#[derive(LoadEnv)]
+struct DelimitedDurations {
+    #[econf(delimiter, humantime)]
+    d1: Vec<Duration>,
+    #[econf(delimiter = ";", humantime)]
+    d2: VecDeque<Duration>,
+    #[econf(delimiter, humantime)]
+    d3: Vec<Duration>,
+}
+
+#[test]
+fn delimited_durations() {
+    std::env::set_var("DELIMITED_DURATIONS_D1", "1m,30s,1h");
+    std::env::set_var("DELIMITED_DURATIONS_D2", "0; 1s 500ms");
+    std::env::set_var("DELIMITED_DURATIONS_D3", "1m,30");
+
+    let a = DelimitedDurations {
+        d1: vec![],
+        d2: VecDeque::new(),
+        d3: vec![Duration::from_secs(9)],
+    };
+
+    let a = econf::load(a, "delimited_durations");
+    assert_eq!(
+        a.d1,
+        [
+            Duration::from_secs(60),
+            Duration::from_secs(30),
+            Duration::from_secs(3600)
+        ]
+    );
+    assert_eq!(a.d2, [Duration::ZERO, Duration::from_millis(1500)]);
+    assert_eq!(a.d3, [Duration::from_secs(9)]);
+}
+
+#[derive(LoadEnv)]
+struct DelimitedSets {
+    #[econf(delimiter)]
+    s1: HashSet<u32>,
+    #[econf(delimiter = " ")]
+    s2: BTreeSet<IpAddr>,
+    #[econf(delimiter)]
+    s3: HashSet<u32>,
+}
+
+#[test]
+fn delimited_sets() {
+    std::env::set_var("DELIMITED_SETS_S1", "1,2,2,3");
+    std::env::set_var("DELIMITED_SETS_S2", "::1 127.0.0.1 ::1");
+    std::env::set_var("DELIMITED_SETS_S3", "1,-1");
+
+    let a = DelimitedSets {
+        s1: HashSet::new(),
+        s2: BTreeSet::new(),
+        s3: HashSet::from([9]),
+    };
+
+    let a = econf::load(a, "delimited_sets");
+    assert_eq!(a.s1, HashSet::from([1, 2, 3]));
+    assert_eq!(
+        a.s2,
+        BTreeSet::from([
+            IpAddr::V4(Ipv4Addr::LOCALHOST),
+            IpAddr::V6(Ipv6Addr::LOCALHOST)
+        ])
+    );
+    assert_eq!(a.s3, HashSet::from([9]));
+}
+
+#[derive(LoadEnv)]
+struct Prefixes {
+    v1: u32,
+    v2: u32,
+    v3: u32,
+    nested: PrefixesNested,
+}
+
+#[derive(LoadEnv)]
+struct PrefixesNested {
+    a: bool,
+    b: bool,
+}
+
+#[test]
+fn prefixes() {
+    std::env::set_var("SERVICE_V1", "1");
+    std::env::set_var("SHARED_V1", "10");
+    std::env::set_var("SHARED_V2", "20");
+    std::env::set_var("SHARED_NESTED_B", "true");
+
+    let a = Prefixes {
+        v1: 0,
+        v2: 0,
+        v3: 0,
+        nested: PrefixesNested { a: false, b: false },
+    };
+
+    let a = econf::load_prefixes(a, &["service", "shared"]);
+    assert_eq!(a.v1, 1);
+    assert_eq!(a.v2, 20);
+    assert_eq!(a.v3, 0);
+    assert!(!a.nested.a);
+    assert!(a.nested.b);
+}
+
+#[derive(LoadEnv)]
+struct Template {
+    host: String,
+    #[econf(example = "8080")]
+    port: u16,
+    #[econf(skip)]
+    _skipped: u32,
+    #[econf(rename = "tls", example = "true")]
+    tls_enabled: bool,
+    nested: TemplateNested,
+    tuple: TS3,
+}
+
+#[derive(LoadEnv)]
+struct TemplateNested {
+    a: bool,
+    b: bool,
+}
+
+#[test]
+fn template() {
+    assert_eq!(
+        econf::template::<Template>("app"),
+        "# APP_HOST=\n\
+         # APP_PORT=8080\n\
+         # APP_TLS=true\n\
+         # APP_NESTED_A=\n\
+         # APP_NESTED_B=\n\
+         # APP_TUPLE_0=\n\
+         # APP_TUPLE_1=\n"
+    );
+}
+
+#[test]
+fn load_errors() {
+    use econf::{LoadError, Loader};
+
+    std::env::set_var("LOAD_ERRORS_PARSE", "x");
+    std::env::set_var("LOAD_ERRORS_VALIDATION", "0");
+    std::env::set_var("LOAD_ERRORS_DUPLICATE", "1");
+
+    let mut loader = Loader::new();
+
+    assert_eq!(
+        loader.try_load_and_map("load_errors_not_found", |s| s.parse::<u32>()),
+        Err(LoadError::NotFound {
+            name: "LOAD_ERRORS_NOT_FOUND".into()
+        })
+    );
+    assert_eq!(
+        loader.try_load_and_map("load_errors_parse", |s| s.parse::<u32>()),
+        Err(LoadError::Parse {
+            name: "LOAD_ERRORS_PARSE".into(),
+            value: "x".into(),
+            msg: "invalid digit found in string".into(),
+        })
+    );
+    assert!(loader.errors().is_empty());
+
+    let v = loader.load_and_validate(
+        5u32,
+        "load_errors_validation",
+        |s| s.parse(),
+        |v| match v {
+            0 => Err("must be positive".into()),
+            _ => Ok(()),
+        },
+    );
+    assert_eq!(v, 5);
+
+    assert_eq!(loader.load_from_str(0u32, "load_errors_duplicate"), 1);
+    assert_eq!(loader.load_from_str(0u32, "load_errors_duplicate"), 1);
+
+    assert_eq!(
+        loader.errors(),
+        [
+            LoadError::Validation {
+                name: "LOAD_ERRORS_VALIDATION".into(),
+                msg: "must be positive".into(),
+            },
+            LoadError::Duplicate {
+                name: "LOAD_ERRORS_DUPLICATE".into()
+            },
+        ]
+    );
+    assert_eq!(
+        loader.errors()[1].to_string(),
+        "LOAD_ERRORS_DUPLICATE is ambiguous"
+    );
+}
+
+#[derive(Default, LoadEnv)]
+struct DbCfg {
+    host: String,
+    port: u16,
+}
+
+#[derive(LoadEnv)]
+struct Keyed {
+    #[econf(keyed)]
+    dbs: HashMap<String, DbCfg>,
+}
+
+#[test]
+fn keyed() {
+    std::env::set_var("KEYED_DBS_PRIMARY_HOST", "db1.local");
+
+    let db = |host: &str| DbCfg {
+        host: host.into(),
+        port: 5432,
+    };
+    let a = Keyed {
+        dbs: HashMap::from([
+            ("primary".to_owned(), db("localhost")),
+            ("replica".to_owned(), db("localhost")),
+        ]),
+    };
+
+    let a = econf::load(a, "keyed");
+    assert_eq!(a.dbs.len(), 2);
+    assert_eq!(a.dbs["primary"].host, "db1.local");
+    assert_eq!(a.dbs["primary"].port, 5432);
+    assert_eq!(a.dbs["replica"].host, "localhost");
+    assert_eq!(a.dbs["replica"].port, 5432);
+}
+
+#[derive(LoadEnv)]
+struct Indexed {
+    #[econf(indexed)]
+    servers: Vec<DbCfg>,
+}
+
+#[test]
+fn indexed() {
+    std::env::set_var("INDEXED_SERVERS_1_HOST", "db2.local");
+    std::env::set_var("INDEXED_SERVERS_1_PORT", "5433");
+    std::env::set_var("INDEXED_SERVERS_2_HOST", "db3.local");
+
+    let db = |host: &str| DbCfg {
+        host: host.into(),
+        port: 5432,
+    };
+    let a = Indexed {
+        servers: vec![db("localhost"), db("localhost")],
+    };
+
+    let a = econf::load(a, "indexed");
+    assert_eq!(a.servers.len(), 2);
+    assert_eq!(a.servers[0].host, "localhost");
+    assert_eq!(a.servers[0].port, 5432);
+    assert_eq!(a.servers[1].host, "db2.local");
+    assert_eq!(a.servers[1].port, 5433);
+}
+
+#[derive(LoadEnv)]
+struct Sparse {
+    #[econf(indexed, sparse)]
+    servers: Vec<DbCfg>,
+    #[econf(indexed, sparse)]
+    grown: Vec<DbCfg>,
+}
+
+#[test]
+fn sparse() {
+    std::env::set_var("SPARSE_SERVERS_0_HOST", "db1.local");
+    std::env::set_var("SPARSE_SERVERS_2_HOST", "db3.local");
+    std::env::set_var("SPARSE_GROWN_2_PORT", "5434");
+
+    let db = |host: &str| DbCfg {
+        host: host.into(),
+        port: 5432,
+    };
+    let a = Sparse {
+        servers: vec![db("a"), db("b"), db("c")],
+        grown: vec![db("a")],
+    };
+
+    let a = econf::load(a, "sparse");
+    assert_eq!(a.servers.len(), 3);
+    assert_eq!(a.servers[0].host, "db1.local");
+    assert_eq!(a.servers[1].host, "b");
+    assert_eq!(a.servers[1].port, 5432);
+    assert_eq!(a.servers[2].host, "db3.local");
+
+    assert_eq!(a.grown.len(), 3);
+    assert_eq!(a.grown[0].host, "a");
+    assert_eq!(a.grown[1].host, "");
+    assert_eq!(a.grown[1].port, 0);
+    assert_eq!(a.grown[2].port, 5434);
+}
+
+#[derive(LoadEnv)]
+struct OnField {
+    v1: u32,
+    v2: u32,
+    #[econf(skip)]
+    _skipped: u32,
+    nested: OnFieldNested,
+}
+
+#[derive(LoadEnv)]
+struct OnFieldNested {
+    a: bool,
+    b: bool,
+}
+
+#[test]
+fn on_field() {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    std::env::set_var("ON_FIELD_V1", "2");
+    std::env::set_var("ON_FIELD_NESTED_B", "true");
+
+    let fields = Rc::new(RefCell::new(vec![]));
+    let mut loader = econf::Loader::new();
+    {
+        let fields = fields.clone();
+        loader.on_field(Box::new(move |name, applied| {
+            fields.borrow_mut().push((name.to_owned(), applied))
+        }));
+    }
+
+    let a = OnField {
+        v1: 1,
+        v2: 1,
+        _skipped: 1,
+        nested: OnFieldNested { a: false, b: false },
+    };
+    let a = a.load("on_field", &mut loader);
+    assert_eq!(a.v1, 2);
+    assert!(a.nested.b);
+
+    let fields = fields.borrow();
+    assert_eq!(fields.len(), 5);
+    assert_eq!(
+        *fields,
+        [
+            ("ON_FIELD_V1".to_owned(), true),
+            ("ON_FIELD_V2".to_owned(), false),
+            ("ON_FIELD_NESTED_A".to_owned(), false),
+            ("ON_FIELD_NESTED_B".to_owned(), true),
+            ("ON_FIELD_NESTED".to_owned(), true),
+        ]
+    );
+}
+
+#[derive(Debug, PartialEq, strum::EnumString, LoadEnv)]
+#[strum(serialize_all = "kebab-case")]
+enum AuthMode {
+    ApiKey,
+    BasicAuth,
+}
+
+#[derive(LoadEnv)]
+struct OptionalEnum {
+    #[econf(from_str)]
+    v1: Option<AuthMode>,
+    #[econf(from_str)]
+    v2: Option<AuthMode>,
+    #[econf(from_str)]
+    v3: Option<AuthMode>,
+}
+
+#[test]
+fn optional_enum() {
+    std::env::set_var("OPTIONAL_ENUM_V1", "basic-auth");
+    std::env::set_var("OPTIONAL_ENUM_V2", "null");
+
+    let a = OptionalEnum {
+        v1: None,
+        v2: Some(AuthMode::ApiKey),
+        v3: Some(AuthMode::ApiKey),
+    };
+
+    let a = econf::load(a, "optional_enum");
+    assert_eq!(a.v1, Some(AuthMode::BasicAuth));
+    assert_eq!(a.v2, None);
+    assert_eq!(a.v3, Some(AuthMode::ApiKey));
+}
+
+#[derive(Debug, PartialEq, strum::EnumString, LoadEnv)]
+enum Hyphenated {
+    #[strum(serialize = "api-key")]
+    ApiKey,
+    #[strum(serialize = "basic-auth", serialize = "basic")]
+    BasicAuth,
+}
+
+#[derive(LoadEnv)]
+struct HyphenatedVariants {
+    v1: Hyphenated,
+    v2: Hyphenated,
+    v3: Hyphenated,
+}
+
+#[test]
+fn hyphenated_variants() {
+    std::env::set_var("HYPHENATED_V1", "api-key");
+    std::env::set_var("HYPHENATED_V2", "basic");
+    std::env::set_var("HYPHENATED_V3", "ApiKey");
+
+    let a = HyphenatedVariants {
+        v1: Hyphenated::BasicAuth,
+        v2: Hyphenated::ApiKey,
+        v3: Hyphenated::BasicAuth,
+    };
+
+    let a = econf::load(a, "hyphenated");
+    assert_eq!(a.v1, Hyphenated::ApiKey);
+    assert_eq!(a.v2, Hyphenated::BasicAuth);
+    assert_eq!(a.v3, Hyphenated::BasicAuth);
+}
+
+#[derive(Debug, PartialEq, LoadEnv)]
+enum RenamedAuth {
+    #[econf(rename = "api-key")]
+    ApiKey,
+    Basic,
+}
+
+#[derive(LoadEnv)]
+struct RenamedVariants {
+    v1: RenamedAuth,
+    v2: RenamedAuth,
+    v3: RenamedAuth,
+}
+
+#[test]
+fn renamed_variants() {
+    std::env::set_var("RENAMED_VARIANTS_V1", "api-key");
+    std::env::set_var("RENAMED_VARIANTS_V2", "Basic");
+    std::env::set_var("RENAMED_VARIANTS_V3", "ApiKey");
+
+    let a = RenamedVariants {
+        v1: RenamedAuth::Basic,
+        v2: RenamedAuth::ApiKey,
+        v3: RenamedAuth::Basic,
+    };
+
+    let (a, report) = econf::load_with_report(a, "renamed_variants");
+    assert_eq!(a.v1, RenamedAuth::ApiKey);
+    assert_eq!(a.v2, RenamedAuth::Basic);
+    assert_eq!(a.v3, RenamedAuth::Basic);
+    assert!(matches!(
+        report.errors(),
+        [econf::LoadError::Parse { msg, .. }] if msg == "unknown variant \"ApiKey\", expected one of api-key, Basic"
+    ));
+}
+
+#[derive(LoadEnv)]
+struct TryLoad {
+    port: u16,
+    host: String,
+    nested: TryLoadNested,
+}
+
+#[derive(LoadEnv)]
+struct TryLoadNested {
+    a: bool,
+    b: bool,
+}
+
+#[test]
+fn try_load() {
+    let a = || TryLoad {
+        port: 8080,
+        host: "localhost".into(),
+        nested: TryLoadNested { a: false, b: false },
+    };
+
+    std::env::set_var("TRY_LOAD_OK_PORT", "80");
+    let ok = econf::try_load(a(), "try_load_ok").ok().unwrap();
+    assert_eq!(ok.port, 80);
+
+    std::env::set_var("TRY_LOAD_PORT", "abc");
+    std::env::set_var("TRY_LOAD_HOST", "example.com");
+    std::env::set_var("TRY_LOAD_NESTED_B", "maybe");
+
+    let e = econf::try_load(a(), "try_load").err().unwrap();
+    assert_eq!(e.errors().len(), 2);
+    assert_eq!(e.errors()[0].name(), "TRY_LOAD_PORT");
+    assert_eq!(e.errors()[1].name(), "TRY_LOAD_NESTED_B");
+    assert_eq!(
+        e.to_string(),
+        "failed to load environment variables\n  \
+         TRY_LOAD_PORT: error on parsing \"abc\": invalid digit found in string\n  \
+         TRY_LOAD_NESTED_B: error on parsing \"maybe\": provided string was not `true` or `false`"
+    );
+
+    let a = econf::load(a(), "try_load");
+    assert_eq!(a.port, 8080);
+    assert_eq!(a.host, "example.com");
+}
+
+#[derive(Default, LoadEnv)]
+struct Defaults {
+    #[econf(default = "8080")]
+    port: u16,
+    #[econf(default = "8080")]
+    overridden: u16,
+    #[econf(default = "[a, b]")]
+    hosts: Vec<String>,
+    untouched: u16,
+    nested: DefaultsNested,
+}
+
+#[derive(Default, LoadEnv)]
+struct DefaultsNested {
+    #[econf(default = "1")]
+    v1: u32,
+}
+
+#[test]
+fn defaults() {
+    use econf::{LoadStatus, Loader};
+
+    std::env::set_var("DEFAULTS_OVERRIDDEN", "80");
+
+    let (a, report) = econf::load_with_report(Defaults::default(), "defaults");
+    assert_eq!(a.port, 8080);
+    assert_eq!(a.overridden, 80);
+    assert_eq!(a.hosts, vec!["a".to_owned(), "b".to_owned()]);
+    assert_eq!(a.untouched, 0);
+    assert_eq!(a.nested.v1, 1);
+
+    // Defaults are not overrides by the environment.
+    assert_eq!(report.loaded_count(), 1);
+    let statuses: Vec<_> = report.records().iter().map(|r| r.status).collect();
+    assert_eq!(
+        statuses,
+        [
+            LoadStatus::Defaulted,
+            LoadStatus::Loaded,
+            LoadStatus::Defaulted,
+            LoadStatus::NotFound,
+            LoadStatus::Defaulted,
+        ]
+    );
+    assert_eq!(report.untouched(), ["DEFAULTS_NESTED"]);
+    assert!(report
+        .to_string()
+        .contains("\n  defaulted: DEFAULTS_PORT, DEFAULTS_HOSTS, DEFAULTS_NESTED_V1"));
+
+    let mut loader = Loader::new();
+    let applied = std::rc::Rc::new(std::cell::RefCell::new(vec![]));
+    let hook = applied.clone();
+    loader.on_field(Box::new(move |name, applied| {
+        hook.borrow_mut().push((name.to_owned(), applied))
+    }));
+    Defaults::default().load("defaults", &mut loader);
+    assert_eq!(loader.loaded_count(), 1);
+    assert!(applied
+        .borrow()
+        .contains(&("DEFAULTS_PORT".to_owned(), false)));
+    assert!(applied
+        .borrow()
+        .contains(&("DEFAULTS_OVERRIDDEN".to_owned(), true)));
+}
+
+use std::borrow::Cow;
+
+#[derive(LoadEnv)]
+struct Cows {
+    v1: Vec<Cow<'static, str>>,
+    v2: Vec<Cow<'static, str>>,
+    m1: HashMap<String, Cow<'static, str>>,
+    m2: HashMap<String, Cow<'static, str>>,
+}
+
+#[test]
+fn cows() {
+    std::env::set_var("COWS_V1", "[x, y]");
+    std::env::set_var("COWS_M1", "{k1: x, k2: y}");
+
+    let a = Cows {
+        v1: vec![Cow::Borrowed("a")],
+        v2: vec![Cow::Borrowed("a"), Cow::Borrowed("b")],
+        m1: HashMap::from([("k1".into(), Cow::Borrowed("a"))]),
+        m2: HashMap::from([("k1".into(), Cow::Borrowed("a"))]),
+    };
+
+    let a = econf::load(a, "cows");
+    assert_eq!(a.v1, vec!["x", "y"]);
+    assert!(matches!(a.v1[0], Cow::Owned(_)));
+    assert_eq!(a.v2, vec!["a", "b"]);
+    assert!(matches!(a.v2[0], Cow::Borrowed(_)));
+    assert_eq!(
+        a.m1,
+        HashMap::from([("k1".into(), "x".into()), ("k2".into(), "y".into())])
+    );
+    assert_eq!(a.m2, HashMap::from([("k1".into(), "a".into())]));
+    assert!(matches!(a.m2["k1"], Cow::Borrowed(_)));
+}
+
+#[derive(LoadEnv)]
+struct Arrays {
+    a1: [u16; 4],
+    a2: [u16; 4],
+    a3: [u16; 4],
+    a4: [String; 2],
+    a5: [bool; 0],
+}
+
+#[test]
+fn arrays() {
+    std::env::set_var("ARRAYS_A1", "[1, 2, 3, 4]");
+    std::env::set_var("ARRAYS_A2", "[1, 2, 3]");
+    std::env::set_var("ARRAYS_A4", "[x, y]");
+    std::env::set_var("ARRAYS_A5", "[]");
+
+    let a = Arrays {
+        a1: [0; 4],
+        a2: [0; 4],
+        a3: [5, 6, 7, 8],
+        a4: ["a".into(), "b".into()],
+        a5: [],
+    };
+
+    let a = econf::load(a, "arrays");
+    assert_eq!(a.a1, [1, 2, 3, 4]);
+    assert_eq!(a.a2, [0; 4]);
+    assert_eq!(a.a3, [5, 6, 7, 8]);
+    assert_eq!(a.a4, ["x".to_owned(), "y".to_owned()]);
+    assert_eq!(a.a5, [] as [bool; 0]);
+
+    // Arrays are parsed in the format of the loader.
+    std::env::set_var("ARRAYS_JSON_A1", "- 1\n- 2\n- 3\n- 4");
+    std::env::set_var("ARRAYS_JSON_A2", "[1, 2, 3, 4]");
+    let mut loader = econf::Loader::new();
+    loader.set_format(econf::Format::Json);
+    let b = Arrays {
+        a1: [0; 4],
+        a2: [0; 4],
+        a3: [0; 4],
+        a4: ["a".into(), "b".into()],
+        a5: [],
+    }
+    .load("arrays_json", &mut loader);
+    assert_eq!(b.a1, [0; 4]);
+    assert_eq!(b.a2, [1, 2, 3, 4]);
+    assert_eq!(loader.errors().len(), 1);
+}
+
+#[derive(LoadEnv)]
+struct CollectNames {
+    v1: u32,
+    #[econf(rename = "another")]
+    v2: u32,
+    #[econf(skip)]
+    _v3: u32,
+    nested: CollectNamesNested,
+    tuple: TS3,
+    seq: Vec<u32>,
+}
+
+#[derive(LoadEnv)]
+struct CollectNamesNested {
+    a: bool,
+    b: bool,
+}
+
+#[test]
+fn collect_names() {
+    assert_eq!(
+        econf::Loader::collect_names::<CollectNames>("collect"),
+        [
+            "COLLECT_V1",
+            "COLLECT_ANOTHER",
+            "COLLECT_NESTED_A",
+            "COLLECT_NESTED_B",
+            "COLLECT_TUPLE_0",
+            "COLLECT_TUPLE_1",
+            "COLLECT_SEQ",
+        ]
+    );
+}
+
+#[test]
+fn env_names() {
+    assert_eq!(
+        CollectNames::env_names("collect"),
+        [
+            ("v1".to_owned(), "COLLECT_V1".to_owned()),
+            ("v2".to_owned(), "COLLECT_ANOTHER".to_owned()),
+            ("nested.a".to_owned(), "COLLECT_NESTED_A".to_owned()),
+            ("nested.b".to_owned(), "COLLECT_NESTED_B".to_owned()),
+            ("tuple.0".to_owned(), "COLLECT_TUPLE_0".to_owned()),
+            ("tuple.1".to_owned(), "COLLECT_TUPLE_1".to_owned()),
+            ("seq".to_owned(), "COLLECT_SEQ".to_owned()),
+        ]
+    );
+    let flattened: Vec<_> = Report::env_names("report")
+        .into_iter()
+        .filter(|(field, _)| field.starts_with("nested"))
+        .collect();
+    assert_eq!(
+        flattened,
+        [
+            ("nested.v1.a".to_owned(), "REPORT_V1_A".to_owned()),
+            ("nested.v1.b".to_owned(), "REPORT_V1_B".to_owned()),
+        ]
+    );
+}
+
+#[test]
+fn env_names_separator() {
+    use econf::{Builder, Loader};
+
+    // The separator given by the struct.
+    assert_eq!(
+        Separator::env_names("separator"),
+        [
+            ("v2_v1".to_owned(), "SEPARATOR__V2_V1".to_owned()),
+            ("v2.v1".to_owned(), "SEPARATOR__V2__V1".to_owned()),
+            ("v2.v2".to_owned(), "SEPARATOR__V2__V2".to_owned()),
+            ("v3".to_owned(), "SEPARATOR__ANOTHER".to_owned()),
+        ]
+    );
+
+    // The separator given by the loader.
+    let mut loader = Builder::new().separator("__").loader();
+    assert_eq!(
+        loader.env_names::<CollectNames>("collect")[2],
+        ("nested.a".to_owned(), "COLLECT__NESTED__A".to_owned())
+    );
+    assert_eq!(
+        loader.env_name("collect", &["nested", "a"]),
+        "COLLECT__NESTED__A"
+    );
+
+    let mut loader = Loader::new();
+    loader.separator(".");
+    assert_eq!(loader.env_name("app", &["db", "port"]), "APP.DB.PORT");
+}
+
+#[derive(Default, LoadEnv)]
+struct ValidateEnv {
+    port: u16,
+    host: String,
+    nested: ValidateEnvNested,
+}
+
+#[derive(Default, LoadEnv)]
+struct ValidateEnvNested {
+    a: bool,
+    b: bool,
+}
+
+#[test]
+fn validate_env() {
+    std::env::set_var("VALIDATE_ENV_OK_PORT", "80");
+    assert_eq!(
+        econf::validate_env::<ValidateEnv>("validate_env_ok"),
+        Ok(())
+    );
+
+    std::env::set_var("VALIDATE_ENV_HOST", "example.com");
+    std::env::set_var("VALIDATE_ENV_NESTED_A", "yes");
+
+    assert_eq!(
+        econf::validate_env::<ValidateEnv>("validate_env"),
+        Err(vec![econf::LoadError::Parse {
+            name: "VALIDATE_ENV_NESTED_A".into(),
+            value: "yes".into(),
+            msg: "provided string was not `true` or `false`".into(),
+        }])
+    );
+}
+
+#[derive(LoadEnv)]
+struct DelimitedPairs {
+    #[econf(delimiter, kv_sep = "=")]
+    m1: BTreeMap<String, u32>,
+    #[econf(delimiter = ";", kv_sep = ":")]
+    m2: HashMap<char, bool>,
+    #[econf(delimiter, kv_sep = "=")]
+    m3: BTreeMap<String, u32>,
+    #[econf(delimiter, kv_sep = "=")]
+    m4: BTreeMap<String, u32>,
+}
+
+#[test]
+fn delimited_pairs() {
+    std::env::set_var("DELIMITED_PAIRS_M1", "a=1,b=2");
+    std::env::set_var("DELIMITED_PAIRS_M2", "x:true;y:false");
+    std::env::set_var("DELIMITED_PAIRS_M3", "a=1,b");
+    std::env::set_var("DELIMITED_PAIRS_M4", "a=1,b=x");
+
+    let fallback = || BTreeMap::from([("z".to_owned(), 0)]);
+    let a = DelimitedPairs {
+        m1: fallback(),
+        m2: HashMap::new(),
+        m3: fallback(),
+        m4: fallback(),
+    };
+
+    let a = econf::load(a, "delimited_pairs");
+    assert_eq!(a.m1, BTreeMap::from([("a".into(), 1), ("b".into(), 2)]));
+    assert_eq!(a.m2, HashMap::from([('x', true), ('y', false)]));
+    assert_eq!(a.m3, fallback());
+    assert_eq!(a.m4, fallback());
+}
+
+#[derive(LoadEnv)]
+struct DelimitedEnumValues {
+    #[econf(delimiter, kv_sep = "=")]
+    m1: HashMap<String, AuthMode>,
+    #[econf(delimiter, kv_sep = "=")]
+    m2: HashMap<String, AuthMode>,
+}
+
+#[test]
+fn delimited_enum_values() {
+    use econf::Loader;
+
+    std::env::set_var("DELIMITED_ENUM_VALUES_M1", "a=api-key, b=basic-auth");
+    std::env::set_var("DELIMITED_ENUM_VALUES_M2", "a=api-key,b=oauth");
+
+    let a = DelimitedEnumValues {
+        m1: HashMap::new(),
+        m2: HashMap::new(),
+    };
+
+    let mut loader = Loader::new();
+    let a = a.load("delimited_enum_values", &mut loader);
+    assert_eq!(
+        a.m1,
+        HashMap::from([
+            ("a".into(), AuthMode::ApiKey),
+            ("b".into(), AuthMode::BasicAuth)
+        ])
+    );
+    assert!(a.m2.is_empty());
+    assert_eq!(
+        loader.errors()[0].to_string(),
+        "DELIMITED_ENUM_VALUES_M2: error on parsing \"a=api-key,b=oauth\": value \"oauth\": Matching variant not found"
+    );
+}
+
+#[derive(LoadEnv)]
+struct SeparatorNested {
+    v1: u32,
+    v2: u32,
+}
+
+#[derive(LoadEnv)]
+#[econf(separator = "__")]
+struct Separator {
+    v2_v1: u32,
+    v2: SeparatorNested,
+    #[econf(rename = "another")]
+    v3: u32,
+}
+
+#[test]
+fn separator() {
+    std::env::set_var("SEPARATOR__V2_V1", "1");
+    std::env::set_var("SEPARATOR__V2__V1", "2");
+    std::env::set_var("SEPARATOR__ANOTHER", "3");
+    std::env::set_var("SEPARATOR_V2_V2", "4");
+
+    let a = Separator {
+        v2_v1: 0,
+        v2: SeparatorNested { v1: 0, v2: 0 },
+        v3: 0,
+    };
+
+    let a = econf::load(a, "separator");
+    assert_eq!(a.v2_v1, 1);
+    assert_eq!(a.v2.v1, 2);
+    assert_eq!(a.v2.v2, 0);
+    assert_eq!(a.v3, 3);
+
+    assert_eq!(
+        econf::Loader::collect_names::<Separator>("separator"),
+        [
+            "SEPARATOR__V2_V1",
+            "SEPARATOR__V2__V1",
+            "SEPARATOR__V2__V2",
+            "SEPARATOR__ANOTHER"
+        ]
+    );
+}
+
+#[derive(Debug, PartialEq, serde::Deserialize, LoadEnv)]
+enum Backend {
+    Local,
+    Remote { host: String, port: u16 },
+    Socket(PathBuf),
+}
+
+#[derive(LoadEnv)]
+struct DataEnums {
+    b1: Backend,
+    b2: Backend,
+    b3: Backend,
+    b4: Backend,
+}
+
+#[test]
+fn data_enums() {
+    std::env::set_var("DATA_ENUMS_B1", "{ Remote: { host: x, port: 9 } }");
+    std::env::set_var("DATA_ENUMS_B2", "Local");
+    std::env::set_var("DATA_ENUMS_B3", "{ Socket: /tmp/sock }");
+
+    let a = DataEnums {
+        b1: Backend::Local,
+        b2: Backend::Socket("/var/sock".into()),
+        b3: Backend::Local,
+        b4: Backend::Local,
+    };
+
+    let a = econf::load(a, "data_enums");
+    assert_eq!(
+        a.b1,
+        Backend::Remote {
+            host: "x".into(),
+            port: 9
+        }
+    );
+    assert_eq!(a.b2, Backend::Local);
+    assert_eq!(a.b3, Backend::Socket("/tmp/sock".into()));
+    assert_eq!(a.b4, Backend::Local);
+}
+
+#[derive(LoadEnv)]
+#[econf(strip_prefix = "server_")]
+struct StripPrefix {
+    server_host: String,
+    server_port: u16,
+    timeout: u64,
+    #[econf(rename = "server_tls")]
+    server_tls_enabled: bool,
+}
+
+#[test]
+fn strip_prefix() {
+    std::env::set_var("STRIP_PREFIX_HOST", "example.com");
+    std::env::set_var("STRIP_PREFIX_SERVER_PORT", "1");
+    std::env::set_var("STRIP_PREFIX_PORT", "80");
+    std::env::set_var("STRIP_PREFIX_TIMEOUT", "5");
+    std::env::set_var("STRIP_PREFIX_SERVER_TLS", "true");
+
+    let a = StripPrefix {
+        server_host: "localhost".into(),
+        server_port: 8080,
+        timeout: 0,
+        server_tls_enabled: false,
+    };
+
+    let a = econf::load(a, "strip_prefix");
+    assert_eq!(a.server_host, "example.com");
+    assert_eq!(a.server_port, 80);
+    assert_eq!(a.timeout, 5);
+    assert!(a.server_tls_enabled);
+}
+
+#[derive(LoadEnv)]
+struct Ipv6Scope {
+    #[econf(ipv6_scope)]
+    a1: Ipv6Addr,
+    #[econf(ipv6_scope)]
+    a2: Ipv6Addr,
+    a3: Ipv6Addr,
+}
+
+#[test]
+fn ipv6_scope() {
+    std::env::set_var("IPV6_SCOPE_A1", "fe80::1%eth0");
+    std::env::set_var("IPV6_SCOPE_A2", "fe80::2");
+    std::env::set_var("IPV6_SCOPE_A3", "fe80::3%eth0");
+
+    let a = Ipv6Scope {
+        a1: Ipv6Addr::LOCALHOST,
+        a2: Ipv6Addr::LOCALHOST,
+        a3: Ipv6Addr::LOCALHOST,
+    };
+
+    let a = econf::load(a, "ipv6_scope");
+    assert_eq!(a.a1, "fe80::1".parse::<Ipv6Addr>().unwrap());
+    assert_eq!(a.a2, "fe80::2".parse::<Ipv6Addr>().unwrap());
+    assert_eq!(a.a3, Ipv6Addr::LOCALHOST);
+}
+
+#[derive(LoadEnv)]
+struct BindAlias {
+    #[econf(bind_alias)]
+    a1: IpAddr,
+    #[econf(bind_alias)]
+    a2: Ipv4Addr,
+    #[econf(bind_alias)]
+    a3: IpAddr,
+    #[econf(bind_alias)]
+    a4: IpAddr,
+    a5: IpAddr,
+}
+
+#[test]
+fn bind_alias() {
+    std::env::set_var("BIND_ALIAS_A1", "*");
+    std::env::set_var("BIND_ALIAS_A2", "0.0.0.0");
+    std::env::set_var("BIND_ALIAS_A3", "192.168.0.1");
+    std::env::set_var("BIND_ALIAS_A4", "::");
+    std::env::set_var("BIND_ALIAS_A5", "*");
+
+    let a = BindAlias {
+        a1: IpAddr::V4(Ipv4Addr::LOCALHOST),
+        a2: Ipv4Addr::LOCALHOST,
+        a3: IpAddr::V4(Ipv4Addr::LOCALHOST),
+        a4: IpAddr::V4(Ipv4Addr::LOCALHOST),
+        a5: IpAddr::V4(Ipv4Addr::LOCALHOST),
+    };
+
+    let a = econf::load(a, "bind_alias");
+    assert_eq!(a.a1, IpAddr::V4(Ipv4Addr::UNSPECIFIED));
+    assert_eq!(a.a2, Ipv4Addr::UNSPECIFIED);
+    assert_eq!(a.a3, IpAddr::V4(Ipv4Addr::new(192, 168, 0, 1)));
+    assert_eq!(a.a4, IpAddr::V6(Ipv6Addr::UNSPECIFIED));
+    assert_eq!(a.a5, IpAddr::V4(Ipv4Addr::LOCALHOST));
+}
+
+#[derive(LoadEnv)]
+struct CaseInsensitive {
+    v1: u32,
+    v2: u32,
+    nested: CaseInsensitiveNested,
+}
+
+#[derive(Default, LoadEnv)]
+struct CaseInsensitiveNested {
+    a: bool,
+    b: bool,
+}
+
+#[test]
+fn case_insensitive() {
+    std::env::set_var("case_insensitive_v1", "1");
+    std::env::set_var("Case_Insensitive_Nested_B", "true");
+
+    let a = || CaseInsensitive {
+        v1: 0,
+        v2: 0,
+        nested: CaseInsensitiveNested::default(),
+    };
+
+    let exact = econf::load(a(), "case_insensitive");
+    assert_eq!(exact.v1, 0);
+    assert!(!exact.nested.b);
+
+    let mut loader = econf::Loader::new();
+    loader.case_insensitive(true);
+    let a = a().load("case_insensitive", &mut loader);
+    assert_eq!(a.v1, 1);
+    assert_eq!(a.v2, 0);
+    assert!(a.nested.b);
+}
+
+#[derive(LoadEnv)]
+struct Sources {
+    v1: u32,
+    v2: Vec<String>,
+    nested: SourcesNested,
+}
+
+#[derive(Default, LoadEnv)]
+struct SourcesNested {
+    a: bool,
+    b: bool,
+}
+
+#[test]
+fn sources() {
+    use econf::Loader;
+
+    std::env::set_var("SOURCES_V1", "100");
+
+    let vars = [("SOURCES_V2", "[a, b]"), ("SOURCES_NESTED_A", "true")];
+    let a = || Sources {
+        v1: 1,
+        v2: vec![],
+        nested: SourcesNested::default(),
+    };
+
+    let map: HashMap<String, String> = vars
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+    let a1 = a().load("sources", &mut Loader::with_source(map));
+    assert_eq!(a1.v1, 1);
+    assert_eq!(a1.v2, vec!["a", "b"]);
+    assert!(a1.nested.a);
+
+    let map: BTreeMap<String, String> = vars
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+    let a2 = a().load("sources", &mut Loader::with_source(map));
+    assert_eq!(a2.v1, 1);
+    assert_eq!(a2.v2, vec!["a", "b"]);
+
+    let a3 = a().load("sources", &mut Loader::with_source(econf::Env));
+    assert_eq!(a3.v1, 100);
+    assert!(a3.v2.is_empty());
+}
+
+#[test]
+fn args_source() {
+    use econf::{ArgsSource, Loader};
+
+    let args = [
+        "app",
+        "--set",
+        "ARGS_V1=1",
+        "--verbose",
+        "--set=ARGS_V2=[a, b]",
+        "--set",
+        "ARGS_NESTED_A=true",
+        "--set",
+        "ARGS_V1=2",
+    ];
+
+    let a = Sources {
+        v1: 100,
+        v2: vec![],
+        nested: SourcesNested::default(),
+    };
+
+    let a = a.load("args", &mut Loader::with_source(ArgsSource::new(args)));
+    assert_eq!(a.v1, 2);
+    assert_eq!(a.v2, vec!["a", "b"]);
+    assert!(a.nested.a);
+}
+
+#[derive(LoadEnv)]
+struct ExpandVars {
+    #[econf(expand_vars)]
+    p1: PathBuf,
+    #[econf(expand_vars)]
+    p2: PathBuf,
+    #[econf(expand_vars = "strict")]
+    p3: PathBuf,
+    #[econf(expand_vars)]
+    s1: String,
+    p4: PathBuf,
+}
+
+#[test]
+fn expand_vars() {
+    std::env::set_var("EXPAND_VARS_HOME", "/home/user");
+    std::env::set_var("EXPAND_VARS_P1", "$EXPAND_VARS_HOME/app");
+    std::env::set_var("EXPAND_VARS_P2", "${EXPAND_VARS_MISSING}/app");
+    std::env::set_var("EXPAND_VARS_P3", "${EXPAND_VARS_MISSING}/app");
+    std::env::set_var("EXPAND_VARS_S1", "${EXPAND_VARS_HOME}_$$1_$");
+    std::env::set_var("EXPAND_VARS_P4", "$EXPAND_VARS_HOME/app");
+
+    let a = ExpandVars {
+        p1: PathBuf::new(),
+        p2: PathBuf::new(),
+        p3: PathBuf::from("/fallback"),
+        s1: String::new(),
+        p4: PathBuf::new(),
+    };
+
+    let a = econf::load(a, "expand_vars");
+    assert_eq!(a.p1, PathBuf::from("/home/user/app"));
+    assert_eq!(a.p2, PathBuf::from("${EXPAND_VARS_MISSING}/app"));
+    assert_eq!(a.p3, PathBuf::from("/fallback"));
+    assert_eq!(a.s1, "/home/user_$1_$");
+    assert_eq!(a.p4, PathBuf::from("$EXPAND_VARS_HOME/app"));
+}
+
+#[derive(LoadEnv)]
+struct Report {
+    v1: u32,
+    v1_a: bool,
+    v1_b: bool,
+    v2: u32,
+    v3: u32,
+    #[econf(flatten)]
+    nested: Report2,
+}
+
+#[derive(LoadEnv)]
+struct Report2 {
+    v1: Report2Nested,
+}
+
+#[derive(Default, LoadEnv)]
+struct Report2Nested {
+    a: bool,
+    b: bool,
+}
+
+#[test]
+fn into_report() {
+    std::env::set_var("REPORT_V1", "1");
+    std::env::set_var("REPORT_V1_A", "true");
+    std::env::set_var("REPORT_V2", "x");
+
+    let mut loader = econf::Loader::new();
+    let a = Report {
+        v1: 0,
+        v1_a: false,
+        v1_b: false,
+        v2: 0,
+        v3: 0,
+        nested: Report2 {
+            v1: Report2Nested::default(),
+        },
+    }
+    .load("report", &mut loader);
+    assert_eq!(a.v1, 1);
+    assert!(a.nested.v1.a);
+
+    assert_eq!(
+        loader.into_report(),
+        "econf: 3 loaded, 3 not found, 1 errors, 2 duplicates\n  \
+         loaded: REPORT_V1, REPORT_V1_A, REPORT_V1_A\n  \
+         error: REPORT_V2: error on parsing \"x\": invalid digit found in string\n  \
+         duplicates: REPORT_V1_A, REPORT_V1_B"
+    );
+}
+
+#[derive(LoadEnv)]
+struct Generic<T, U> {
+    v1: T,
+    v2: Vec<U>,
+    #[econf(skip)]
+    _v3: std::marker::PhantomData<fn(U)>,
+}
+
+#[derive(LoadEnv)]
+#[econf(bound = "T: std::str::FromStr, T::Err: std::fmt::Display")]
+struct Bound<T> {
+    #[econf(delimiter)]
+    v1: Vec<T>,
+    v2: u32,
+}
+
+#[test]
+fn generic_bounds() {
+    std::env::set_var("GENERIC_V1", "2");
+    std::env::set_var("GENERIC_V2", "[a, b]");
+    std::env::set_var("BOUND_V1", "1,2,3");
+    std::env::set_var("BOUND_V2", "4");
+
+    let a = Generic::<u32, String> {
+        v1: 1,
+        v2: vec![],
+        _v3: std::marker::PhantomData,
+    };
+    let a = econf::load(a, "generic");
+    assert_eq!(a.v1, 2);
+    assert_eq!(a.v2, vec!["a", "b"]);
+
+    let b = econf::load(Bound::<u8> { v1: vec![], v2: 0 }, "bound");
+    assert_eq!(b.v1, vec![1, 2, 3]);
+    assert_eq!(b.v2, 4);
+}
+
+#[derive(LoadEnv)]
+struct Markers<T> {
+    v1: u32,
+    _v2: std::marker::PhantomData<T>,
+    _v3: std::marker::PhantomPinned,
+}
+
+#[test]
+fn markers() {
+    std::env::set_var("MARKERS_V1", "2");
+
+    let a = Markers::<String> {
+        v1: 1,
+        _v2: std::marker::PhantomData,
+        _v3: std::marker::PhantomPinned,
+    };
+    let (a, report) = econf::load_with_report(a, "markers");
+    assert_eq!(a.v1, 2);
+    assert_eq!(report.records().len(), 1);
+    assert_eq!(
+        econf::template::<Markers<String>>("markers"),
+        "# MARKERS_V1=\n"
+    );
+}
+
+#[derive(LoadEnv)]
+struct Reversed {
+    v1: std::cmp::Reverse<u32>,
+    v2: std::cmp::Reverse<Vec<u32>>,
+    v3: BinaryHeap<std::cmp::Reverse<u32>>,
+}
+
+#[test]
+fn reversed() {
+    use std::cmp::Reverse;
+
+    std::env::set_var("REVERSED_V1", "5");
+    std::env::set_var("REVERSED_V2", "[1, 2]");
+    std::env::set_var("REVERSED_V3", "[3, 1, 2]");
+
+    let a = Reversed {
+        v1: Reverse(1),
+        v2: Reverse(vec![]),
+        v3: BinaryHeap::new(),
+    };
+    let mut a = econf::load(a, "reversed");
+    assert_eq!(a.v1, Reverse(5));
+    assert_eq!(a.v2, Reverse(vec![1, 2]));
+    assert_eq!(a.v3.pop(), Some(Reverse(1)));
+    assert_eq!(a.v3.pop(), Some(Reverse(2)));
+    assert_eq!(a.v3.pop(), Some(Reverse(3)));
+}
+
+#[derive(LoadEnv)]
+struct Subsystems {
+    touched: SubsystemNested,
+    untouched: SubsystemNested,
+    deep: Subsystem,
+}
+
+#[derive(LoadEnv)]
+struct Subsystem {
+    touched: SubsystemNested,
+    untouched: SubsystemNested,
+}
+
+#[derive(Default, LoadEnv)]
+struct SubsystemNested {
+    a: bool,
+    b: bool,
+}
+
+#[test]
+fn untouched() {
+    std::env::set_var("UNTOUCHED_TOUCHED_B", "true");
+    std::env::set_var("UNTOUCHED_DEEP_TOUCHED_B", "true");
+
+    let a = Subsystems {
+        touched: SubsystemNested::default(),
+        untouched: SubsystemNested::default(),
+        deep: Subsystem {
+            touched: SubsystemNested::default(),
+            untouched: SubsystemNested::default(),
+        },
+    };
+    let (a, report) = econf::load_with_report(a, "untouched");
+    assert!(a.touched.b);
+    assert!(a.deep.touched.b);
+    assert_eq!(
+        report.untouched(),
+        ["UNTOUCHED_UNTOUCHED", "UNTOUCHED_DEEP_UNTOUCHED"]
+    );
+}
+
+#[derive(LoadEnv)]
+struct Inspected {
+    port: u16,
+    #[econf(rename = "port")]
+    other_port: u16,
+    host: String,
+}
+
+#[test]
+fn load_inspect() {
+    use econf::{LoadError, LoadStatus};
+
+    std::env::set_var("INSPECTED_PORT", "80");
+    std::env::set_var("INSPECTED_PROT", "81");
+
+    let a = Inspected {
+        port: 8080,
+        other_port: 8080,
+        host: "localhost".into(),
+    };
+    let (a, loader) = econf::load_inspect(a, "inspected");
+    assert_eq!(a.port, 80);
+    assert_eq!(a.other_port, 80);
+    assert_eq!(
+        loader.errors(),
+        [LoadError::Duplicate {
+            name: "INSPECTED_PORT".into()
+        }]
+    );
+    let statuses: Vec<_> = loader.records().iter().map(|r| r.status).collect();
+    assert_eq!(
+        statuses,
+        [LoadStatus::Loaded, LoadStatus::Loaded, LoadStatus::NotFound]
+    );
+    assert_eq!(loader.unused_vars("inspected"), ["INSPECTED_PROT"]);
+    assert_eq!(loader.report().loaded_count(), 2);
+}
+
+#[derive(LoadEnv)]
+struct WithReport {
+    v1: u32,
+    v2: u32,
+    v3: u32,
+}
+
+#[test]
+fn load_with_report() {
+    use econf::{LoadRecord, LoadStatus};
+
+    std::env::set_var("WITH_REPORT_V1", "1");
+    std::env::set_var("WITH_REPORT_V3", "x");
+
+    let (a, report) = econf::load_with_report(
+        WithReport {
+            v1: 0,
+            v2: 0,
+            v3: 0,
+        },
+        "with_report",
+    );
+    assert_eq!(a.v1, 1);
+    assert_eq!(
+        report.records(),
+        [
+            LoadRecord {
+                name: "WITH_REPORT_V1".into(),
+                status: LoadStatus::Loaded
+            },
+            LoadRecord {
+                name: "WITH_REPORT_V2".into(),
+                status: LoadStatus::NotFound
+            },
+            LoadRecord {
+                name: "WITH_REPORT_V3".into(),
+                status: LoadStatus::Failed
+            },
+        ]
+    );
+    assert_eq!(report.errors().len(), 1);
+}
+
+#[derive(LoadEnv)]
+struct FieldPrefixes {
+    #[econf(prefixes = ["field_app", "field_global"])]
+    region: String,
+    #[econf(prefixes = ["field_app", "field_global"])]
+    zone: String,
+    #[econf(prefixes = ["field_app", "field_global"], rename = "tier")]
+    level: u32,
+    #[econf(prefixes = ["field_app", "field_global"])]
+    nested: FieldPrefixesNested,
+    other: String,
+}
+
+#[derive(Default, LoadEnv)]
+struct FieldPrefixesNested {
+    a: bool,
+    b: bool,
+}
+
+#[test]
+fn field_prefixes() {
+    std::env::set_var("FIELD_APP_ZONE", "a");
+    std::env::set_var("FIELD_GLOBAL_ZONE", "b");
+    std::env::set_var("FIELD_GLOBAL_REGION", "eu");
+    std::env::set_var("FIELD_GLOBAL_TIER", "2");
+    std::env::set_var("FIELD_GLOBAL_NESTED_B", "true");
+    std::env::set_var("FIELD_GLOBAL_OTHER", "x");
+
+    let a = FieldPrefixes {
+        region: String::new(),
+        zone: String::new(),
+        level: 0,
+        nested: FieldPrefixesNested::default(),
+        other: String::new(),
+    };
+
+    let a = econf::load(a, "field_prefixes");
+    assert_eq!(a.region, "eu");
+    assert_eq!(a.zone, "a");
+    assert_eq!(a.level, 2);
+    assert!(a.nested.b);
+    assert_eq!(a.other, "");
+}
+
+#[derive(LoadEnv)]
+struct CiKeys {
+    #[econf(ci_keys)]
+    l: HashMap<String, u32>,
+}
+
+#[test]
+fn ci_keys() {
+    use econf::{LoadError, Loader};
+
+    let map = HashMap::from([
+        ("APP_L_FOO".to_owned(), "1".to_owned()),
+        ("APP_L_Foo".to_owned(), "2".to_owned()),
+        ("APP_L_BAR".to_owned(), "3".to_owned()),
+        ("APP_L_BAD".to_owned(), "x".to_owned()),
+    ]);
+
+    let a = CiKeys {
+        l: HashMap::from([("Bar".to_owned(), 0), ("baz".to_owned(), 4)]),
+    };
+
+    let mut loader = Loader::with_source(map);
+    let a = a.load("app", &mut loader);
+    assert_eq!(
+        a.l,
+        HashMap::from([
+            ("foo".to_owned(), 2),
+            ("bar".to_owned(), 3),
+            ("baz".to_owned(), 4),
+        ])
+    );
+    assert!(matches!(loader.errors(), [LoadError::Parse { name, .. }] if name == "APP_L_BAD"));
+}
+
+mod parsers {
+    pub fn csv(s: &str) -> Result<Vec<u32>, std::num::ParseIntError> {
+        s.split(',').map(|v| v.trim().parse()).collect()
+    }
+}
+
+#[derive(LoadEnv)]
+struct ParseWith {
+    #[econf(parse_with = "parsers::csv")]
+    v1: Vec<u32>,
+    #[econf(parse_with = "parsers::csv")]
+    v2: Vec<u32>,
+    #[econf(parse_with = "econf::parse::duration_float_secs")]
+    v3: Duration,
+}
+
+#[test]
+fn parse_with() {
+    std::env::set_var("PARSE_WITH_V1", "1, 2,3");
+    std::env::set_var("PARSE_WITH_V2", "1,x");
+    std::env::set_var("PARSE_WITH_V3", "0.5");
+
+    let a = ParseWith {
+        v1: vec![],
+        v2: vec![9],
+        v3: Duration::ZERO,
+    };
+
+    let a = econf::load(a, "parse_with");
+    assert_eq!(a.v1, vec![1, 2, 3]);
+    assert_eq!(a.v2, vec![9]);
+    assert_eq!(a.v3, Duration::from_millis(500));
+}
+
+#[derive(LoadEnv)]
+struct ByteSize {
+    #[econf(bytesize)]
+    buffer: NonZeroUsize,
+    #[econf(bytesize)]
+    zero: NonZeroUsize,
+    #[econf(bytesize)]
+    limit: u64,
+    #[econf(bytesize)]
+    small: u8,
+}
+
+#[test]
+fn bytesize() {
+    std::env::set_var("BYTESIZE_BUFFER", "4KiB");
+    std::env::set_var("BYTESIZE_ZERO", "0KiB");
+    std::env::set_var("BYTESIZE_LIMIT", "1.5GB");
+    std::env::set_var("BYTESIZE_SMALL", "1KiB");
+
+    let one = NonZeroUsize::new(1).unwrap();
+    let a = ByteSize {
+        buffer: one,
+        zero: one,
+        limit: 1,
+        small: 1,
+    };
+
+    let a = econf::load(a, "bytesize");
+    assert_eq!(a.buffer.get(), 4096);
+    assert_eq!(a.zero, one);
+    assert_eq!(a.limit, 1);
+    assert_eq!(a.small, 1);
+}
+
+#[derive(LoadEnv)]
+struct HumanNumber {
+    #[econf(bytesize)]
+    b1: u64,
+    #[econf(bytesize)]
+    b2: u64,
+    #[econf(human_number)]
+    n1: u32,
+    #[econf(human_number)]
+    n2: i64,
+    #[econf(human_number)]
+    n3: u32,
+    plain: u32,
+}
+
+#[test]
+fn human_number() {
+    std::env::set_var("HUMAN_NUMBER_B1", "10MB");
+    std::env::set_var("HUMAN_NUMBER_B2", "10_000_000");
+    std::env::set_var("HUMAN_NUMBER_N1", "10_000");
+    std::env::set_var("HUMAN_NUMBER_N2", "-3M");
+    std::env::set_var("HUMAN_NUMBER_N3", "10X");
+    std::env::set_var("HUMAN_NUMBER_PLAIN", "10_000");
+
+    let a = HumanNumber {
+        b1: 0,
+        b2: 0,
+        n1: 0,
+        n2: 0,
+        n3: 1,
+        plain: 1,
+    };
+
+    let (a, report) = econf::load_with_report(a, "human_number");
+    assert_eq!(a.b1, 10_000_000);
+    assert_eq!(a.b1, a.b2);
+    assert_eq!(a.n1, 10_000);
+    assert_eq!(a.n2, -3_000_000);
+    assert_eq!(a.n3, 1);
+    assert_eq!(a.plain, 1);
+    assert_eq!(
+        report.errors()[0],
+        econf::LoadError::Parse {
+            name: "HUMAN_NUMBER_N3".into(),
+            value: "10X".into(),
+            msg: "unknown suffix \"x\" in \"10X\"".into(),
+        }
+    );
+}
+
+#[derive(LoadEnv)]
+struct DenyUnknown {
+    port: u16,
+    nested: DenyUnknownNested,
+    #[econf(skip)]
+    skipped: u32,
+}
+
+#[derive(Default, LoadEnv)]
+struct DenyUnknownNested {
+    a: bool,
+    b: bool,
+}
+
+#[test]
+fn deny_unknown() {
+    use econf::{LoadError, Loader};
+
+    let map = HashMap::from([
+        ("APP_PORT".to_owned(), "80".to_owned()),
+        ("APP_PROT".to_owned(), "9090".to_owned()),
+        ("APP_NESTED_A".to_owned(), "true".to_owned()),
+        ("APP_NESTED_C".to_owned(), "true".to_owned()),
+        ("APP_SKIPPED".to_owned(), "1".to_owned()),
+        ("OTHER_PORT".to_owned(), "1".to_owned()),
+    ]);
+    let a = || DenyUnknown {
+        port: 8080,
+        nested: DenyUnknownNested::default(),
+        skipped: 0,
+    };
+
+    let mut loader = Loader::with_source(map.clone());
+    let _ = a().load("app", &mut loader);
+    assert_eq!(
+        loader.unused_vars("app"),
+        ["APP_NESTED_C", "APP_PROT", "APP_SKIPPED"]
+    );
+    assert!(loader.finish("app").is_ok());
+
+    let mut loader = Loader::with_source(map);
+    loader.deny_unknown(true);
+    let a = a().load("app", &mut loader);
+    assert_eq!(a.port, 80);
+    assert!(a.nested.a);
+
+    let e = loader.finish("app").unwrap_err();
+    assert_eq!(
+        e.errors(),
+        [
+            LoadError::Unknown {
+                name: "APP_NESTED_C".into()
+            },
+            LoadError::Unknown {
+                name: "APP_PROT".into()
+            },
+            LoadError::Unknown {
+                name: "APP_SKIPPED".into()
+            },
+        ]
+    );
+}
+
+#[derive(LoadEnv)]
+struct Pointers {
+    x: Box<u32>,
+    nested: Box<PointersNested>,
+    rc: std::rc::Rc<u32>,
+    arc: std::sync::Arc<Vec<u32>>,
+    name: std::sync::Arc<str>,
+    boxed: Box<str>,
+}
+
+#[derive(Default, LoadEnv)]
+struct PointersNested {
+    a: bool,
+    b: bool,
+}
+
+#[test]
+fn pointers() {
+    use std::rc::Rc;
+    use std::sync::Arc;
+
+    std::env::set_var("POINTERS_X", "2");
+    std::env::set_var("POINTERS_NESTED_B", "true");
+    std::env::set_var("POINTERS_RC", "3");
+    std::env::set_var("POINTERS_ARC", "[1, 2]");
+    std::env::set_var("POINTERS_NAME", "econf");
+    std::env::set_var("POINTERS_BOXED", "boxed");
+
+    let shared = Rc::new(1);
+    let a = Pointers {
+        x: Box::new(1),
+        nested: Box::default(),
+        rc: shared.clone(),
+        arc: Arc::new(vec![]),
+        name: "".into(),
+        boxed: "".into(),
+    };
+
+    let a = econf::load(a, "pointers");
+    assert_eq!(*a.x, 2);
+    assert!(a.nested.b);
+    assert_eq!(*a.rc, 3);
+    assert_eq!(*shared, 1);
+    assert_eq!(*a.arc, vec![1, 2]);
+    assert_eq!(&*a.name, "econf");
+    assert_eq!(&*a.boxed, "boxed");
+}
+
+#[derive(LoadEnv)]
+struct CowStr {
+    c1: Cow<'static, str>,
+    c2: Cow<'static, str>,
+}
+
+#[test]
+fn cow_str() {
+    std::env::set_var("COW_STR_C1", "found");
+
+    let a = CowStr {
+        c1: Cow::Borrowed("default"),
+        c2: Cow::Borrowed("default"),
+    };
+
+    let a = econf::load(a, "cow_str");
+    assert!(matches!(a.c1, Cow::Owned(ref s) if s == "found"));
+    assert!(matches!(a.c2, Cow::Borrowed("default")));
+}
+
+#[test]
+fn default_source() {
+    std::env::set_var("DEFAULT_SOURCE_V1", "100");
+
+    let a = || Sources {
+        v1: 1,
+        v2: vec![],
+        nested: SourcesNested::default(),
+    };
+
+    econf::set_default_source(HashMap::from([
+        ("DEFAULT_SOURCE_V2".to_owned(), "[a, b]".to_owned()),
+        ("DEFAULT_SOURCE_NESTED_A".to_owned(), "true".to_owned()),
+    ]));
+    let a1 = econf::load(a(), "default_source");
+    assert_eq!(a1.v1, 1);
+    assert_eq!(a1.v2, vec!["a", "b"]);
+    assert!(a1.nested.a);
+
+    // Other threads still read the process environment.
+    let a2 = std::thread::spawn(move || econf::load(a(), "default_source"))
+        .join()
+        .unwrap();
+    assert_eq!(a2.v1, 100);
+    assert!(a2.v2.is_empty());
+
+    econf::reset_default_source();
+    let a3 = econf::load(a(), "default_source");
+    assert_eq!(a3.v1, 100);
+    assert!(a3.v2.is_empty());
+}
+
+#[derive(LoadEnv)]
+struct SkipIfEmpty {
+    #[econf(skip_if_empty)]
+    name: String,
+    #[econf(skip_if_empty)]
+    title: Option<String>,
+    #[econf(skip_if_empty)]
+    set: String,
+    #[econf(skip_if_empty, default = "d")]
+    defaulted: String,
+    kept: String,
+}
+
+#[test]
+fn skip_if_empty() {
+    std::env::set_var("SKIP_IF_EMPTY_NAME", "");
+    std::env::set_var("SKIP_IF_EMPTY_TITLE", "");
+    std::env::set_var("SKIP_IF_EMPTY_SET", "x");
+    std::env::set_var("SKIP_IF_EMPTY_DEFAULTED", "");
+    std::env::set_var("SKIP_IF_EMPTY_KEPT", "");
+
+    let a = SkipIfEmpty {
+        name: "name".into(),
+        title: Some("title".into()),
+        set: "".into(),
+        defaulted: "".into(),
+        kept: "kept".into(),
+    };
+
+    let a = econf::load(a, "skip_if_empty");
+    assert_eq!(a.name, "name");
+    assert_eq!(a.title.as_deref(), Some("title"));
+    assert_eq!(a.set, "x");
+    assert_eq!(a.defaulted, "d");
+    assert_eq!(a.kept, "");
+}
+
+#[test]
+fn env_name() {
+    assert_eq!(econf::env_name("app", &["v2", "v1"]), "APP_V2_V1");
+    assert_eq!(econf::env_name("app", &[]), "APP");
+    assert_eq!(econf::env_name("", &["port"]), "PORT");
+    assert_eq!(econf::env_name("app_", &["_port"]), "APP_PORT");
+
+    // Agrees with the names the derive macro loads.
+    let names = econf::Loader::collect_names::<CollectNames>("collect");
+    assert_eq!(names[0], econf::env_name("collect", &["v1"]));
+    assert_eq!(names[2], econf::env_name("collect", &["nested", "a"]));
+    assert_eq!(names[4], econf::env_name("collect", &["tuple", "0"]));
+}
+
+#[derive(Debug, PartialEq, serde::Deserialize)]
+struct Rule {
+    path: String,
+    allow: bool,
+}
+
+#[derive(LoadEnv)]
+struct ContainerFile {
+    rules: Vec<Rule>,
+    json: HashMap<String, u32>,
+    set: Vec<u32>,
+    missing: Vec<u32>,
+    broken: Vec<u32>,
+}
+
+#[test]
+fn container_file() {
+    let dir = std::env::temp_dir().join(format!("econf-container-file-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let rules = dir.join("rules.yaml");
+    std::fs::write(
+        &rules,
+        "- path: /a\n  allow: true\n- path: /b\n  allow: false\n",
+    )
+    .unwrap();
+    let json = dir.join("json.json");
+    std::fs::write(&json, r#"{"a": 1, "b": 2}"#).unwrap();
+
+    std::env::set_var("CONTAINER_FILE_RULES_FILE", &rules);
+    std::env::set_var("CONTAINER_FILE_JSON_FILE", &json);
+    std::env::set_var("CONTAINER_FILE_SET", "[1]");
+    std::env::set_var("CONTAINER_FILE_SET_FILE", &json);
+    std::env::set_var("CONTAINER_FILE_MISSING_FILE", dir.join("missing.yaml"));
+    std::env::set_var("CONTAINER_FILE_BROKEN_FILE", &json);
+
+    let a = || ContainerFile {
+        rules: vec![],
+        json: HashMap::new(),
+        set: vec![],
+        missing: vec![9],
+        broken: vec![9],
+    };
+
+    // `_FILE` is ignored unless the fallback is on.
+    let (b, report) = econf::load_with_report(a(), "container_file");
+    assert!(b.rules.is_empty());
+    assert!(b.json.is_empty());
+    assert_eq!(b.set, [1]);
+    assert_eq!(b.missing, [9]);
+    assert!(report.errors().is_empty());
+
+    let mut loader = econf::Loader::new();
+    loader.file_fallback(true);
+    let a = a().load("container_file", &mut loader);
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(
+        a.rules,
+        [
+            Rule {
+                path: "/a".into(),
+                allow: true
+            },
+            Rule {
+                path: "/b".into(),
+                allow: false
+            },
+        ]
+    );
+    assert_eq!(a.json, HashMap::from([("a".into(), 1), ("b".into(), 2)]));
+    assert_eq!(a.set, [1]);
+    assert_eq!(a.missing, [9]);
+    assert_eq!(a.broken, [9]);
+    assert_eq!(loader.errors().len(), 2);
+}
+
+#[derive(LoadEnv)]
+struct FileFallback {
+    password: String,
+    port: u16,
+    set: u16,
+    missing: String,
+    unset: String,
+    ports: Vec<u16>,
+    data: PathBuf,
+}
+
+#[test]
+fn file_fallback() {
+    use econf::{LoadError, Loader};
+
+    let dir = std::env::temp_dir().join(format!("econf-file-fallback-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let password = dir.join("password");
+    std::fs::write(&password, "hunter2\n").unwrap();
+    let port = dir.join("port");
+    std::fs::write(&port, " 8443 ").unwrap();
+    let ports = dir.join("ports");
+    std::fs::write(&ports, "[80, 443]").unwrap();
+    let data = dir.join("data");
+    std::fs::write(&data, "/var/lib/app\n").unwrap();
+
+    let missing = dir.join("missing");
+    let map = HashMap::from([
+        (
+            "APP_PASSWORD_FILE".to_owned(),
+            password.display().to_string(),
+        ),
+        ("APP_PORT_FILE".to_owned(), port.display().to_string()),
+        ("APP_SET".to_owned(), "80".to_owned()),
+        ("APP_SET_FILE".to_owned(), port.display().to_string()),
+        ("APP_MISSING_FILE".to_owned(), missing.display().to_string()),
+        ("APP_PORTS_FILE".to_owned(), ports.display().to_string()),
+        ("APP_DATA_FILE".to_owned(), data.display().to_string()),
+    ]);
+    let a = || FileFallback {
+        password: "x".into(),
+        port: 1,
+        set: 1,
+        missing: "x".into(),
+        unset: "x".into(),
+        ports: vec![],
+        data: PathBuf::new(),
+    };
+
+    let mut loader = Loader::with_source(map.clone());
+    let b = a().load("app", &mut loader);
+    assert_eq!(b.password, "x");
+    assert_eq!(b.port, 1);
+    assert!(b.ports.is_empty());
+    assert_eq!(b.data, PathBuf::new());
+
+    let mut loader = Loader::with_source(map);
+    loader.file_fallback(true);
+    let b = a().load("app", &mut loader);
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(b.password, "hunter2");
+    assert_eq!(b.port, 8443);
+    assert_eq!(b.set, 80);
+    assert_eq!(b.missing, "x");
+    assert_eq!(b.unset, "x");
+    assert_eq!(b.ports, [80, 443]);
+    assert_eq!(b.data, PathBuf::from("/var/lib/app"));
+    assert_eq!(loader.unused_vars("app"), ["APP_SET_FILE"]);
+
+    let Err(e) = loader.finish("app") else {
+        panic!("the missing file is an error");
+    };
+    assert!(matches!(
+        e.errors(),
+        [LoadError::Parse { name, .. }] if name == "APP_MISSING_FILE"
+    ));
+}
+
+#[derive(LoadEnv)]
+struct JsonFormat {
+    map: HashMap<String, String>,
+    yaml: Vec<u32>,
+    escaped: Vec<String>,
+}
+
+#[test]
+fn json_format() {
+    use econf::{Format, Loader};
+
+    std::env::set_var("JSON_FORMAT_MAP", r#"{"on": "y", "off": "n"}"#);
+    std::env::set_var("JSON_FORMAT_YAML", "- 1\n- 2");
+    std::env::set_var("JSON_FORMAT_ESCAPED", r#"["tab\tx", "é"]"#);
+
+    let a = || JsonFormat {
+        map: HashMap::new(),
+        yaml: vec![],
+        escaped: vec![],
+    };
+
+    let yaml = a().load("json_format", &mut Loader::new());
+    assert_eq!(yaml.yaml, [1, 2]);
+
+    let mut loader = Loader::new();
+    loader.set_format(Format::Json);
+    let json = a().load("json_format", &mut loader);
+    assert_eq!(
+        json.map,
+        HashMap::from([("on".into(), "y".into()), ("off".into(), "n".into())])
+    );
+    assert!(json.yaml.is_empty());
+    assert_eq!(json.escaped, ["tab\tx", "é"]);
+    assert_eq!(loader.errors().len(), 1);
+}
+
+#[derive(LoadEnv)]
+#[econf(case = "preserve")]
+#[allow(non_snake_case)]
+struct PreservedCase {
+    mixedCase: u32,
+    lower: u32,
+    nested: PreservedCaseNested,
+}
+
+#[derive(Default, LoadEnv)]
+struct PreservedCaseNested {
+    a: bool,
+    b: bool,
+}
+
+#[derive(LoadEnv)]
+struct CasePreserve {
+    v1: u32,
+    preserved: PreservedCase,
+}
+
+#[test]
+fn case_preserve() {
+    std::env::set_var("CASE_PRESERVE_V1", "1");
+    std::env::set_var("CASE_PRESERVE_PRESERVED_mixedCase", "2");
+    std::env::set_var("CASE_PRESERVE_PRESERVED_LOWER", "3");
+    std::env::set_var("CASE_PRESERVE_PRESERVED_nested_a", "true");
+
+    let a = CasePreserve {
+        v1: 0,
+        preserved: PreservedCase {
+            mixedCase: 0,
+            lower: 0,
+            nested: PreservedCaseNested::default(),
+        },
+    };
+
+    let a = econf::load(a, "case_preserve");
+    assert_eq!(a.v1, 1);
+    assert_eq!(a.preserved.mixedCase, 2);
+    assert_eq!(a.preserved.lower, 0);
+    assert!(a.preserved.nested.a);
+
+    assert_eq!(
+        econf::Loader::collect_names::<CasePreserve>("case_preserve"),
+        [
+            "CASE_PRESERVE_V1",
+            "CASE_PRESERVE_PRESERVED_mixedCase",
+            "CASE_PRESERVE_PRESERVED_lower",
+            "CASE_PRESERVE_PRESERVED_nested_a",
+            "CASE_PRESERVE_PRESERVED_nested_b",
+        ]
+    );
+}
+
+#[derive(LoadEnv)]
+#[econf(rename_all = "kebab-case")]
+struct RenameAllKebab {
+    my_field: String,
+    #[econf(rename = "other")]
+    my_other_field: u32,
+}
+
+#[derive(LoadEnv)]
+#[econf(rename_all = "camelCase", verbatim)]
+struct RenameAllCamel {
+    my_field: String,
+    nested_bool: RenameAllCamelNested,
+}
+
+#[derive(Default, LoadEnv)]
+struct RenameAllCamelNested {
+    a: bool,
+    b: bool,
+}
+
+#[derive(LoadEnv)]
+#[econf(rename_all = "PascalCase", strip_prefix = "server_", verbatim)]
+struct RenameAllPascal {
+    server_host_name: String,
+}
+
+#[test]
+fn rename_all() {
+    std::env::set_var("RENAME_ALL_MY-FIELD", "kebab");
+    std::env::set_var("RENAME_ALL_OTHER", "2");
+    std::env::set_var("rename_all_myField", "camel");
+    std::env::set_var("rename_all_nestedBool_a", "true");
+    std::env::set_var("rename_all_HostName", "pascal");
+
+    let a = RenameAllKebab {
+        my_field: "".into(),
+        my_other_field: 0,
+    };
+    let a = econf::load(a, "rename_all");
+    assert_eq!(a.my_field, "kebab");
+    assert_eq!(a.my_other_field, 2);
+
+    let b = RenameAllCamel {
+        my_field: "".into(),
+        nested_bool: RenameAllCamelNested::default(),
+    };
+    let b = econf::load(b, "rename_all");
+    assert_eq!(b.my_field, "camel");
+    assert!(b.nested_bool.a);
+
+    let c = RenameAllPascal {
+        server_host_name: "".into(),
+    };
+    let c = econf::load(c, "rename_all");
+    assert_eq!(c.server_host_name, "pascal");
+}
+
+#[derive(LoadEnv)]
+struct NestedKeys {
+    #[econf(nested_keys)]
+    cfg: HashMap<String, serde_yaml::Value>,
+    #[econf(nested_keys, sep = "__")]
+    opts: HashMap<String, serde_yaml::Value>,
+}
+
+#[test]
+fn nested_keys() {
+    let map = HashMap::from([
+        ("APP_CFG_A_B".to_owned(), "1".to_owned()),
+        ("APP_CFG_A_C".to_owned(), "x".to_owned()),
+        ("APP_CFG_D".to_owned(), "[1, 2]".to_owned()),
+        ("APP_OPTS_LOG__MAX_LEVEL".to_owned(), "2".to_owned()),
+    ]);
+
+    let a = NestedKeys {
+        cfg: HashMap::from([("e".to_owned(), serde_yaml::Value::Bool(true))]),
+        opts: HashMap::new(),
+    };
+
+    let a = a.load("app", &mut econf::Loader::with_source(map));
+    let yaml = |s: &str| serde_yaml::from_str::<serde_yaml::Value>(s).unwrap();
+    assert_eq!(
+        a.cfg,
+        HashMap::from([
+            ("a".to_owned(), yaml("{b: 1, c: x}")),
+            ("d".to_owned(), yaml("[1, 2]")),
+            ("e".to_owned(), yaml("true")),
+        ])
+    );
+    assert_eq!(
+        a.opts,
+        HashMap::from([("log".to_owned(), yaml("{max_level: 2}"))])
+    );
+}
+
+#[derive(Debug, PartialEq, strum::EnumString, LoadEnv)]
+#[econf(by_index_or_name)]
+enum Level {
+    Low,
+    Medium,
+    High,
+}
+
+#[derive(Debug, PartialEq, LoadEnv)]
+#[econf(ascii_case_insensitive)]
+enum Mode {
+    Fast,
+    Safe,
+}
+
+#[derive(Debug, PartialEq, LoadEnv)]
+#[econf(ascii_case_insensitive, by_index_or_name)]
+enum Priority {
+    Low,
+    High,
+}
+
+#[derive(LoadEnv)]
+struct AsciiCaseInsensitive {
+    v1: Mode,
+    v2: Mode,
+    v3: Mode,
+    v4: Priority,
+    v5: Priority,
+}
+
+#[test]
+fn ascii_case_insensitive() {
+    use econf::Loader;
+
+    std::env::set_var("ASCII_CASE_INSENSITIVE_V1", "safe");
+    std::env::set_var("ASCII_CASE_INSENSITIVE_V2", " SAFE ");
+    std::env::set_var("ASCII_CASE_INSENSITIVE_V3", "slow");
+    std::env::set_var("ASCII_CASE_INSENSITIVE_V4", "1");
+    std::env::set_var("ASCII_CASE_INSENSITIVE_V5", "hIgH");
+
+    let a = AsciiCaseInsensitive {
+        v1: Mode::Fast,
+        v2: Mode::Fast,
+        v3: Mode::Fast,
+        v4: Priority::Low,
+        v5: Priority::Low,
+    };
+
+    let mut loader = Loader::new();
+    let a = a.load("ascii_case_insensitive", &mut loader);
+    assert_eq!(a.v1, Mode::Safe);
+    assert_eq!(a.v2, Mode::Safe);
+    assert_eq!(a.v3, Mode::Fast);
+    assert_eq!(a.v4, Priority::High);
+    assert_eq!(a.v5, Priority::High);
+    assert_eq!(
+        loader.errors()[0].to_string(),
+        "ASCII_CASE_INSENSITIVE_V3: error on parsing \"slow\": unknown variant \"slow\", expected one of Fast, Safe"
+    );
+}
+
+#[derive(LoadEnv)]
+struct ByIndexOrName {
+    v1: Level,
+    v2: Level,
+    v3: Level,
+    v4: Level,
+}
+
+#[test]
+fn by_index_or_name() {
+    std::env::set_var("BY_INDEX_OR_NAME_V1", "2");
+    std::env::set_var("BY_INDEX_OR_NAME_V2", "Medium");
+    std::env::set_var("BY_INDEX_OR_NAME_V3", "3");
+    std::env::set_var("BY_INDEX_OR_NAME_V4", "Unknown");
+
+    let a = ByIndexOrName {
+        v1: Level::Low,
+        v2: Level::Low,
+        v3: Level::Low,
+        v4: Level::Low,
+    };
+
+    let a = econf::load(a, "by_index_or_name");
+    assert_eq!(a.v1, Level::High);
+    assert_eq!(a.v2, Level::Medium);
+    assert_eq!(a.v3, Level::Low);
+    assert_eq!(a.v4, Level::Low);
+}
+
+#[derive(Default, LoadEnv)]
+struct LoadMut {
+    v1: u32,
+    v2: String,
+    nested: LoadMutNested,
+}
+
+#[derive(Default, LoadEnv)]
+struct LoadMutNested {
+    a: bool,
+    b: bool,
+}
+
+#[test]
+fn load_mut() {
+    std::env::set_var("LOAD_MUT_V1", "2");
+    std::env::set_var("LOAD_MUT_NESTED_B", "true");
+
+    struct Owner {
+        config: LoadMut,
+    }
+
+    let mut owner = Owner {
+        config: LoadMut {
+            v1: 1,
+            v2: "kept".into(),
+            nested: LoadMutNested { a: true, b: false },
+        },
+    };
+
+    econf::load_mut(&mut owner.config, "load_mut");
+    assert_eq!(owner.config.v1, 2);
+    assert_eq!(owner.config.v2, "kept");
+    assert!(owner.config.nested.a);
+    assert!(owner.config.nested.b);
+}
+
+#[derive(Default, LoadEnv)]
+struct DefaultTrue {
+    #[econf(default_true)]
+    absent: bool,
+    #[econf(default_true)]
+    disabled: bool,
+    #[econf(default_true)]
+    broken: bool,
+}
+
+#[test]
+fn default_true() {
+    std::env::set_var("DEFAULT_TRUE_DISABLED", "false");
+    std::env::set_var("DEFAULT_TRUE_BROKEN", "nope");
+
+    let a = econf::load(DefaultTrue::default(), "default_true");
+    assert!(a.absent);
+    assert!(!a.disabled);
+    assert!(!a.broken);
+}
+
+#[derive(LoadEnv)]
+struct PoolCfg {
+    size: u32,
+    idle: u32,
+}
+
+#[derive(LoadEnv)]
+struct FieldPrefix {
+    #[econf(prefix = "db_pool")]
+    pool: PoolCfg,
+    #[econf(prefix = "field_prefix_shared", absolute)]
+    shared: PoolCfg,
+    #[econf(prefix = "pool", prefixes = ["field_prefix_app", "field_prefix_global"])]
+    global: PoolCfg,
+}
+
+#[test]
+fn field_prefix() {
+    std::env::set_var("FIELD_PREFIX_DB_POOL_SIZE", "1");
+    std::env::set_var("FIELD_PREFIX_SHARED_SIZE", "2");
+    std::env::set_var("FIELD_PREFIX_GLOBAL_POOL_IDLE", "3");
+
+    let pool = || PoolCfg { size: 0, idle: 0 };
+    let a = FieldPrefix {
+        pool: pool(),
+        shared: pool(),
+        global: pool(),
+    };
+
+    let a = econf::load(a, "field_prefix");
+    assert_eq!(a.pool.size, 1);
+    assert_eq!(a.shared.size, 2);
+    assert_eq!(a.global.idle, 3);
+
+    assert_eq!(
+        econf::Loader::collect_names::<FieldPrefix>("field_prefix"),
+        [
+            "FIELD_PREFIX_DB_POOL_SIZE",
+            "FIELD_PREFIX_DB_POOL_IDLE",
+            "FIELD_PREFIX_SHARED_SIZE",
+            "FIELD_PREFIX_SHARED_IDLE",
+            "FIELD_PREFIX_APP_POOL_SIZE",
+            "FIELD_PREFIX_APP_POOL_IDLE",
+        ]
+    );
+}
+
+#[test]
+fn loaded_count() {
+    use econf::Loader;
+
+    std::env::set_var("LOADED_COUNT_V1", "1");
+    std::env::set_var("LOADED_COUNT_V3", "x");
+
+    let a = || WithReport {
+        v1: 0,
+        v2: 0,
+        v3: 0,
+    };
+
+    let mut loader = Loader::new();
+    let _ = a().load("loaded_count", &mut loader);
+    assert_eq!(loader.loaded_count(), 1);
+
+    let (_, report) = econf::load_with_report(a(), "loaded_count");
+    assert_eq!(report.loaded_count(), 1);
+
+    let (_, report) = econf::load_with_report(a(), "loaded_count_none");
+    assert_eq!(report.loaded_count(), 0);
+}
+
+#[derive(Debug, PartialEq, LoadEnv)]
+struct WithOptions {
+    host: String,
+    port: u16,
+    tags: Vec<String>,
+    inner: WithOptionsInner,
+}
+
+#[derive(Debug, PartialEq, LoadEnv)]
+struct WithOptionsInner {
+    timeout: u32,
+}
+
+#[derive(Debug, LoadEnv)]
+struct WithOptionsDuplicate {
+    inner_timeout: u32,
+    inner: WithOptionsInner,
+}
+
+#[test]
+fn load_with_options() {
+    use econf::{Format, LoadError, LoadOptions};
+
+    let init = || WithOptions {
+        host: "localhost".into(),
+        port: 8080,
+        tags: vec![],
+        inner: WithOptionsInner { timeout: 1 },
+    };
+    let mut source = HashMap::from([
+        ("app__host".to_owned(), "".to_owned()),
+        ("APP__PORT".to_owned(), "null".to_owned()),
+        ("APP__TAGS".to_owned(), r#"["a", "b"]"#.to_owned()),
+        ("SHARED__INNER__TIMEOUT".to_owned(), "5".to_owned()),
+    ]);
+    let opts = LoadOptions::new()
+        .source(source.clone())
+        .separator("__")
+        .case_insensitive(true)
+        .skip_if_empty(true)
+        .null_tokens(&["null"])
+        .fallback_prefixes(&["SHARED"])
+        .format(Format::Json)
+        .deny_unknown(true);
+
+    let a = econf::load_with_options(init(), "app", opts).unwrap();
+    assert_eq!(
+        a,
+        WithOptions {
+            host: "localhost".into(),
+            port: 8080,
+            tags: vec!["a".into(), "b".into()],
+            inner: WithOptionsInner { timeout: 5 },
+        }
+    );
+
+    source.insert("APP__PROT".into(), "9090".into());
+    source.insert("APP__INNER__TIMEOUT".into(), "x".into());
+    let opts = LoadOptions::new()
+        .source(source)
+        .separator("__")
+        .deny_unknown(true);
+
+    let errors = econf::load_with_options(init(), "APP", opts).unwrap_err();
+    let names: Vec<_> = errors.iter().map(|e| e.name()).collect();
+    assert_eq!(names, ["APP__PORT", "APP__INNER__TIMEOUT", "APP__PROT"]);
+    assert!(matches!(errors[2], LoadError::Unknown { .. }));
+
+    let dup = || WithOptionsDuplicate {
+        inner_timeout: 1,
+        inner: WithOptionsInner { timeout: 1 },
+    };
+    let source = HashMap::<String, String>::new();
+    let opts = LoadOptions::new().source(source.clone());
+    assert!(econf::load_with_options(dup(), "APP", opts).is_ok());
+
+    let opts = LoadOptions::new().source(source).deny_duplicates(true);
+    let errors = econf::load_with_options(dup(), "APP", opts).unwrap_err();
+    assert_eq!(
+        errors,
+        [LoadError::Duplicate {
+            name: "APP_INNER_TIMEOUT".into()
+        }]
+    );
+}
+
+#[test]
+fn builder() {
+    use econf::{Builder, Format, LoadOptions};
+
+    let init = || WithOptions {
+        host: "localhost".into(),
+        port: 8080,
+        tags: vec![],
+        inner: WithOptionsInner { timeout: 1 },
+    };
+    let source = HashMap::from([
+        ("builder__port".to_owned(), "80".to_owned()),
+        ("BUILDER__TAGS".to_owned(), r#"["a"]"#.to_owned()),
+        ("BUILDER__INNER__TIMEOUT".to_owned(), "x".to_owned()),
+    ]);
+
+    let a = Builder::new()
+        .prefix("builder")
+        .separator("__")
+        .format(Format::Json)
+        .case_insensitive(true)
+        .source(source.clone())
+        .load(init());
+    assert_eq!(a.port, 80);
+    assert_eq!(a.tags, ["a"]);
+    assert_eq!(a.inner.timeout, 1);
+
+    let errors = Builder::new()
+        .prefix("builder")
+        .options(LoadOptions::new().separator("__").source(source))
+        .try_load(init())
+        .unwrap_err();
+    assert_eq!(errors[0].name(), "BUILDER__INNER__TIMEOUT");
+
+    let loader = Builder::new().separator("__").loader();
+    assert_eq!(loader.join("a", "b"), "a__b");
+}
+
+#[derive(LoadEnv)]
+struct Ranges {
+    r1: std::ops::Range<u16>,
+    r2: std::ops::RangeInclusive<u16>,
+    r3: std::ops::Range<u16>,
+    r4: std::ops::RangeInclusive<i32>,
+    r5: std::ops::Range<u16>,
+}
+
+#[test]
+fn ranges() {
+    use econf::Loader;
+
+    std::env::set_var("RANGES_R1", "1000..2000");
+    std::env::set_var("RANGES_R2", "1000..=2000");
+    std::env::set_var("RANGES_R3", "{ start: 10, end: 20 }");
+    std::env::set_var("RANGES_R4", "-5 ..= 5");
+    std::env::set_var("RANGES_R5", "2000..1000");
+
+    let a = Ranges {
+        r1: 0..1,
+        r2: 0..=1,
+        r3: 0..1,
+        r4: 0..=1,
+        r5: 0..1,
+    };
+
+    let mut loader = Loader::new();
+    let a = a.load("ranges", &mut loader);
+    assert_eq!(a.r1, 1000..2000);
+    assert_eq!(a.r2, 1000..=2000);
+    assert_eq!(a.r3, 10..20);
+    assert_eq!(a.r4, -5..=5);
+    assert_eq!(a.r5, 0..1);
+    assert_eq!(loader.errors().len(), 1);
+    assert_eq!(loader.errors()[0].name(), "RANGES_R5");
+}
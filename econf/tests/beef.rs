@@ -0,0 +1,30 @@
+#![cfg(feature = "beef")]
+
+use econf::LoadEnv;
+
+#[derive(LoadEnv)]
+struct Beef {
+    wide: beef::Cow<'static, str>,
+    lean: beef::lean::Cow<'static, str>,
+    unset: beef::lean::Cow<'static, str>,
+}
+
+#[test]
+fn beef() {
+    std::env::set_var("BEEF_WIDE", "wide");
+    std::env::set_var("BEEF_LEAN", "lean");
+
+    let a = Beef {
+        wide: beef::Cow::borrowed("default"),
+        lean: beef::lean::Cow::borrowed("default"),
+        unset: beef::lean::Cow::borrowed("default"),
+    };
+
+    let a = econf::load(a, "beef");
+    assert_eq!(a.wide, "wide");
+    assert!(a.wide.is_owned());
+    assert_eq!(a.lean, "lean");
+    assert!(a.lean.is_owned());
+    assert_eq!(a.unset, "default");
+    assert!(a.unset.is_borrowed());
+}
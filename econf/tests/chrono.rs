@@ -0,0 +1,45 @@
+#![cfg(feature = "chrono")]
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use econf::LoadEnv;
+
+#[derive(LoadEnv)]
+struct Times {
+    cutoff: DateTime<Utc>,
+    offset: DateTime<Utc>,
+    day: NaiveDate,
+    at: NaiveDateTime,
+    broken: DateTime<Utc>,
+}
+
+#[test]
+fn chrono() {
+    std::env::set_var("TIMES_CUTOFF", "2024-01-02T03:04:05Z");
+    std::env::set_var("TIMES_OFFSET", "2024-01-02T12:04:05+09:00");
+    std::env::set_var("TIMES_DAY", "2024-01-02");
+    std::env::set_var("TIMES_AT", "2024-01-02T03:04:05.5");
+    std::env::set_var("TIMES_BROKEN", "yesterday");
+
+    let epoch = DateTime::<Utc>::UNIX_EPOCH;
+    let a = Times {
+        cutoff: epoch,
+        offset: epoch,
+        day: NaiveDate::default(),
+        at: NaiveDateTime::default(),
+        broken: epoch,
+    };
+
+    let a = econf::load(a, "times");
+    let expected = Utc.with_ymd_and_hms(2024, 1, 2, 3, 4, 5).unwrap();
+    assert_eq!(a.cutoff, expected);
+    assert_eq!(a.offset, expected);
+    assert_eq!(a.day, NaiveDate::from_ymd_opt(2024, 1, 2).unwrap());
+    assert_eq!(
+        a.at,
+        NaiveDate::from_ymd_opt(2024, 1, 2)
+            .unwrap()
+            .and_hms_milli_opt(3, 4, 5, 500)
+            .unwrap()
+    );
+    assert_eq!(a.broken, epoch);
+}
@@ -0,0 +1,54 @@
+#![cfg(feature = "config-source")]
+
+use econf::EconfSource;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+#[derive(Debug, Deserialize)]
+struct Server {
+    host: String,
+    port: u16,
+    tls: bool,
+}
+
+#[test]
+fn config_source() {
+    std::env::set_var("CONFIG_SOURCE_PORT", "8080");
+    std::env::set_var("CONFIG_SOURCE_TLS", "true");
+    std::env::set_var("CONFIG_SOURCE_", "ignored");
+
+    let server: Server = config::Config::builder()
+        .set_default("host", "localhost")
+        .unwrap()
+        .set_default("port", 80)
+        .unwrap()
+        .set_default("tls", false)
+        .unwrap()
+        .add_source(EconfSource::new("config_source"))
+        .build()
+        .unwrap()
+        .try_deserialize()
+        .unwrap();
+
+    assert_eq!(server.host, "localhost");
+    assert_eq!(server.port, 8080);
+    assert!(server.tls);
+}
+
+#[test]
+fn config_source_default_source() {
+    std::env::set_var("CONFIG_DEFAULT_SOURCE_PORT", "8080");
+    econf::set_default_source(HashMap::from([(
+        "CONFIG_DEFAULT_SOURCE_HOST".to_owned(),
+        "example.com".to_owned(),
+    )]));
+
+    let config = config::Config::builder()
+        .add_source(EconfSource::new("config_default_source"))
+        .build()
+        .unwrap();
+    econf::reset_default_source();
+
+    assert_eq!(config.get::<String>("host").unwrap(), "example.com");
+    assert!(config.get::<u16>("port").is_err());
+}
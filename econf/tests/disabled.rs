@@ -0,0 +1,36 @@
+#![cfg(feature = "disabled")]
+
+use econf::{LoadEnv, Loader};
+
+#[derive(LoadEnv)]
+struct Disabled {
+    v1: u32,
+    #[econf(default = "3")]
+    v2: u32,
+    v3_a: u32,
+    v3: DisabledInner,
+}
+
+#[derive(LoadEnv)]
+struct DisabledInner {
+    a: u32,
+}
+
+#[test]
+fn disabled() {
+    std::env::set_var("DISABLED_V1", "2");
+
+    let a = Disabled {
+        v1: 1,
+        v2: 1,
+        v3_a: 1,
+        v3: DisabledInner { a: 1 },
+    };
+
+    let mut loader = Loader::new();
+    let a = a.load("disabled", &mut loader);
+    assert_eq!(a.v1, 1);
+    assert_eq!(a.v2, 3);
+    assert_eq!(loader.errors().len(), 1);
+    assert_eq!(loader.errors()[0].name(), "DISABLED_V3_A");
+}
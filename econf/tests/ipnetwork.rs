@@ -0,0 +1,39 @@
+#![cfg(feature = "ipnetwork")]
+
+use econf::LoadEnv;
+use ipnetwork::{IpNetwork, Ipv4Network, Ipv6Network};
+
+#[derive(LoadEnv)]
+struct Networks {
+    subnet: IpNetwork,
+    v4: Ipv4Network,
+    v6: Ipv6Network,
+    host: Ipv4Network,
+    broken: Ipv4Network,
+}
+
+#[test]
+fn ipnetwork() {
+    std::env::set_var("NETWORKS_SUBNET", "10.0.0.0/8");
+    std::env::set_var("NETWORKS_V4", "192.168.0.0/16");
+    std::env::set_var("NETWORKS_V6", "fe80::/10");
+    std::env::set_var("NETWORKS_HOST", "10.1.2.3");
+    std::env::set_var("NETWORKS_BROKEN", "10.0.0.0/33");
+
+    let any: Ipv4Network = "0.0.0.0/0".parse().unwrap();
+    let a = Networks {
+        subnet: IpNetwork::V4(any),
+        v4: any,
+        v6: "::/0".parse().unwrap(),
+        host: any,
+        broken: any,
+    };
+
+    let a = econf::load(a, "networks");
+    assert_eq!(a.subnet, "10.0.0.0/8".parse::<IpNetwork>().unwrap());
+    assert!(a.subnet.contains("10.20.30.40".parse().unwrap()));
+    assert_eq!(a.v4.prefix(), 16);
+    assert_eq!(a.v6.prefix(), 10);
+    assert_eq!(a.host.prefix(), 32);
+    assert_eq!(a.broken, any);
+}
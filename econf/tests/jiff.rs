@@ -0,0 +1,37 @@
+#![cfg(feature = "jiff")]
+
+use econf::LoadEnv;
+use jiff::{Span, Timestamp, ToSpan};
+
+#[derive(LoadEnv)]
+struct Jiff {
+    cutoff: Timestamp,
+    iso: Span,
+    friendly: Span,
+    broken_time: Timestamp,
+    broken_span: Span,
+}
+
+#[test]
+fn jiff() {
+    std::env::set_var("JIFF_CUTOFF", "2024-01-02T03:04:05Z");
+    std::env::set_var("JIFF_ISO", "PT1h30m");
+    std::env::set_var("JIFF_FRIENDLY", "2 days 3h");
+    std::env::set_var("JIFF_BROKEN_TIME", "yesterday");
+    std::env::set_var("JIFF_BROKEN_SPAN", "forever");
+
+    let a = Jiff {
+        cutoff: Timestamp::UNIX_EPOCH,
+        iso: Span::new(),
+        friendly: Span::new(),
+        broken_time: Timestamp::UNIX_EPOCH,
+        broken_span: 1.second(),
+    };
+
+    let a = econf::load(a, "jiff");
+    assert_eq!(a.cutoff, Timestamp::from_second(1704164645).unwrap());
+    assert_eq!(a.iso.fieldwise(), 1.hour().minutes(30));
+    assert_eq!(a.friendly.fieldwise(), 2.days().hours(3));
+    assert_eq!(a.broken_time, Timestamp::UNIX_EPOCH);
+    assert_eq!(a.broken_span.fieldwise(), 1.second());
+}
@@ -0,0 +1,464 @@
+use econf::LoadEnv;
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use std::num::{Saturating, Wrapping};
+use std::sync::{Mutex, Once};
+
+struct Capture;
+
+static CAPTURE: Capture = Capture;
+static LOGS: Mutex<Vec<(Level, String)>> = Mutex::new(Vec::new());
+
+impl Log for Capture {
+    fn enabled(&self, _: &Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &Record) {
+        LOGS.lock()
+            .unwrap()
+            .push((record.level(), record.args().to_string()));
+    }
+
+    fn flush(&self) {}
+}
+
+fn init() {
+    static INIT: Once = Once::new();
+    INIT.call_once(|| {
+        log::set_logger(&CAPTURE).unwrap();
+        log::set_max_level(LevelFilter::Trace);
+    });
+}
+
+fn logged(level: Level, message: &str) -> bool {
+    LOGS.lock()
+        .unwrap()
+        .iter()
+        .any(|(l, m)| *l == level && m == message)
+}
+
+#[derive(LoadEnv)]
+struct OutOfRange {
+    s1: Saturating<u8>,
+    s2: Saturating<i8>,
+    s3: Saturating<u8>,
+    w1: Wrapping<u8>,
+    w2: Wrapping<u8>,
+}
+
+#[test]
+fn out_of_range() {
+    init();
+
+    std::env::set_var("OUT_OF_RANGE_S1", "300");
+    std::env::set_var("OUT_OF_RANGE_S2", "-200");
+    std::env::set_var("OUT_OF_RANGE_S3", "100");
+    std::env::set_var("OUT_OF_RANGE_W1", "300");
+    std::env::set_var("OUT_OF_RANGE_W2", "x");
+
+    let a = OutOfRange {
+        s1: Saturating(1),
+        s2: Saturating(1),
+        s3: Saturating(1),
+        w1: Wrapping(1),
+        w2: Wrapping(1),
+    };
+
+    let a = econf::load(a, "out_of_range");
+    assert_eq!(a.s1, Saturating(255));
+    assert_eq!(a.s2, Saturating(-128));
+    assert_eq!(a.s3, Saturating(100));
+    assert_eq!(a.w1, Wrapping(44));
+    assert_eq!(a.w2, Wrapping(1));
+
+    assert!(logged(
+        Level::Warn,
+        "econf: loading OUT_OF_RANGE_S1: 300 is out of range, saturated to 255"
+    ));
+    assert!(logged(
+        Level::Warn,
+        "econf: loading OUT_OF_RANGE_S2: -200 is out of range, saturated to -128"
+    ));
+    assert!(logged(
+        Level::Warn,
+        "econf: loading OUT_OF_RANGE_W1: 300 is out of range, wrapped to 44"
+    ));
+    assert!(!logged(
+        Level::Warn,
+        "econf: loading OUT_OF_RANGE_S3: 100 is out of range, saturated to 100"
+    ));
+}
+
+#[derive(LoadEnv)]
+struct ArrayLength {
+    v: [u16; 4],
+}
+
+#[test]
+fn array_length() {
+    init();
+
+    std::env::set_var("ARRAY_LENGTH_V", "[1, 2, 3]");
+
+    let a = econf::load(ArrayLength { v: [0; 4] }, "array_length");
+    assert_eq!(a.v, [0; 4]);
+
+    assert!(logged(
+        Level::Error,
+        "econf: loading ARRAY_LENGTH_V: error on parsing \"[1, 2, 3]\": expected 4 elements, found 3"
+    ));
+}
+
+#[derive(LoadEnv)]
+struct Tls {
+    cert: String,
+    key: String,
+}
+
+#[derive(LoadEnv)]
+struct Flatten {
+    cert: String,
+    #[econf(flatten)]
+    tls: Tls,
+}
+
+#[test]
+fn flatten() {
+    init();
+
+    std::env::set_var("FLATTEN_CERT", "cert.pem");
+    std::env::set_var("FLATTEN_KEY", "key.pem");
+    std::env::set_var("FLATTEN_TLS_KEY", "ignored.pem");
+
+    let a = Flatten {
+        cert: String::new(),
+        tls: Tls {
+            cert: String::new(),
+            key: String::new(),
+        },
+    };
+
+    let a = econf::load(a, "flatten");
+    assert_eq!(a.cert, "cert.pem");
+    assert_eq!(a.tls.cert, "cert.pem");
+    assert_eq!(a.tls.key, "key.pem");
+
+    assert!(logged(
+        Level::Warn,
+        "econf: warning: FLATTEN_CERT is ambiguous"
+    ));
+    assert!(!logged(
+        Level::Warn,
+        "econf: warning: FLATTEN_KEY is ambiguous"
+    ));
+    assert_eq!(
+        econf::Loader::collect_names::<Flatten>("flatten"),
+        ["FLATTEN_CERT", "FLATTEN_CERT", "FLATTEN_KEY"]
+    );
+}
+
+#[derive(LoadEnv)]
+struct Secret {
+    user: String,
+    #[econf(secret)]
+    passwd: String,
+    #[econf(secret)]
+    pin: u32,
+}
+
+#[test]
+fn secret() {
+    init();
+
+    std::env::set_var("SECRET_USER", "admin");
+    std::env::set_var("SECRET_PASSWD", "hunter2");
+    std::env::set_var("SECRET_PIN", "12ab");
+
+    let a = Secret {
+        user: String::new(),
+        passwd: String::new(),
+        pin: 0,
+    };
+
+    let a = econf::load(a, "secret");
+    assert_eq!(a.user, "admin");
+    assert_eq!(a.passwd, "hunter2");
+    assert_eq!(a.pin, 0);
+
+    assert!(logged(
+        Level::Info,
+        "econf: loading SECRET_USER: found admin"
+    ));
+    assert!(logged(
+        Level::Info,
+        "econf: loading SECRET_PASSWD: found <redacted>"
+    ));
+    assert!(logged(
+        Level::Error,
+        "econf: loading SECRET_PIN: error on parsing \"<redacted>\": <redacted>"
+    ));
+    assert!(!LOGS
+        .lock()
+        .unwrap()
+        .iter()
+        .any(|(_, m)| m.contains("hunter2") || m.contains("12ab")));
+}
+
+#[derive(LoadEnv)]
+struct SecretDefault {
+    #[econf(secret, default = "hunter3")]
+    passwd: String,
+}
+
+#[test]
+fn secret_default() {
+    init();
+
+    let a = econf::load(
+        SecretDefault {
+            passwd: String::new(),
+        },
+        "secret_default",
+    );
+    assert_eq!(a.passwd, "hunter3");
+
+    assert!(logged(
+        Level::Info,
+        "econf: loading SECRET_DEFAULT_PASSWD: not found, using default <redacted>"
+    ));
+    assert!(!LOGS
+        .lock()
+        .unwrap()
+        .iter()
+        .any(|(_, m)| m.contains("hunter3")));
+}
+
+#[derive(LoadEnv)]
+struct SecretInvalid {
+    #[econf(secret, bytesize)]
+    limit: u64,
+    #[econf(secret)]
+    port: Wrapping<u8>,
+}
+
+#[test]
+fn secret_invalid() {
+    init();
+
+    std::env::set_var("SECRET_INVALID_LIMIT", "12xyzzy");
+    std::env::set_var("SECRET_INVALID_PORT", "300");
+
+    let a = SecretInvalid {
+        limit: 1,
+        port: Wrapping(0),
+    };
+
+    let (a, report) = econf::load_with_report(a, "secret_invalid");
+    assert_eq!(a.limit, 1);
+    assert_eq!(a.port, Wrapping(44));
+    assert!(matches!(
+        report.errors(),
+        [econf::LoadError::Parse { value, msg, .. }] if value == "<redacted>" && msg == "<redacted>"
+    ));
+
+    assert!(logged(
+        Level::Error,
+        "econf: loading SECRET_INVALID_LIMIT: error on parsing \"<redacted>\": <redacted>"
+    ));
+    assert!(logged(
+        Level::Warn,
+        "econf: loading SECRET_INVALID_PORT: <redacted> is out of range, wrapped to <redacted>"
+    ));
+    assert!(!LOGS
+        .lock()
+        .unwrap()
+        .iter()
+        .any(|(_, m)| m.contains("SECRET_INVALID") && (m.contains("xyzzy") || m.contains("300"))));
+}
+
+#[derive(LoadEnv)]
+struct NoteSkipped {
+    v1: u32,
+    #[econf(skip)]
+    v2: u32,
+    #[econf(skip)]
+    v3: u32,
+}
+
+#[test]
+fn note_skipped() {
+    init();
+
+    std::env::set_var("NOTE_SKIPPED_V2", "2");
+
+    let a = NoteSkipped {
+        v1: 1,
+        v2: 1,
+        v3: 1,
+    };
+
+    let a = econf::load(a, "note_skipped");
+    assert_eq!(a.v2, 1);
+
+    assert!(logged(
+        Level::Warn,
+        "econf: warning: NOTE_SKIPPED_V2 is set but the field is skipped"
+    ));
+    assert!(!LOGS
+        .lock()
+        .unwrap()
+        .iter()
+        .any(|(_, m)| m.contains("NOTE_SKIPPED_V3")));
+}
+
+#[derive(LoadEnv)]
+struct WarnIfDefault {
+    #[econf(warn_if_default)]
+    v1: String,
+    #[econf(warn_if_default)]
+    v2: u32,
+    v3: u32,
+}
+
+#[test]
+fn warn_if_default() {
+    init();
+
+    std::env::set_var("WARN_IF_DEFAULT_V2", "2");
+
+    let a = WarnIfDefault {
+        v1: String::new(),
+        v2: 0,
+        v3: 0,
+    };
+
+    let a = econf::load(a, "warn_if_default");
+    assert_eq!(a.v2, 2);
+
+    assert!(logged(
+        Level::Warn,
+        "econf: warning: WARN_IF_DEFAULT_V1 has the default value"
+    ));
+    assert!(!LOGS.lock().unwrap().iter().any(|(l, m)| *l == Level::Warn
+        && (m.contains("WARN_IF_DEFAULT_V2") || m.contains("WARN_IF_DEFAULT_V3"))));
+}
+
+#[derive(LoadEnv)]
+struct RenameCollision {
+    #[econf(rename = "port")]
+    v1: u16,
+    #[econf(rename = "PORT")]
+    v2: u16,
+    port_other: u16,
+}
+
+#[test]
+fn rename_collision() {
+    init();
+
+    std::env::set_var("RENAME_COLLISION_PORT", "80");
+
+    let a = RenameCollision {
+        v1: 0,
+        v2: 0,
+        port_other: 0,
+    };
+
+    let (a, report) = econf::load_with_report(a, "rename_collision");
+    assert_eq!(a.v1, 80);
+    assert_eq!(a.v2, 80);
+
+    assert!(logged(
+        Level::Warn,
+        "econf: warning: RENAME_COLLISION_PORT is ambiguous"
+    ));
+    assert_eq!(
+        report.errors(),
+        [econf::LoadError::Duplicate {
+            name: "RENAME_COLLISION_PORT".into()
+        }]
+    );
+}
+
+#[derive(LoadEnv)]
+struct Quiet {
+    x: u32,
+    y: u32,
+}
+
+#[test]
+fn verbose() {
+    init();
+
+    std::env::set_var("QUIET_X", "2");
+
+    let opts = econf::LoadOptions::new().verbose(false);
+    let a = econf::load_with_options(Quiet { x: 1, y: 1 }, "quiet", opts).unwrap();
+    assert_eq!(a.x, 2);
+    assert_eq!(a.y, 1);
+
+    assert!(logged(Level::Info, "econf: loading QUIET_X: found 2"));
+    assert!(logged(Level::Trace, "econf: loading QUIET_Y: not found"));
+    assert!(!logged(Level::Info, "econf: loading QUIET_Y: not found"));
+
+    econf::load(Quiet { x: 1, y: 1 }, "loud");
+    assert!(logged(Level::Info, "econf: loading LOUD_Y: not found"));
+}
+
+#[derive(LoadEnv)]
+struct InvalidSuffix {
+    #[econf(human_number)]
+    count: u32,
+}
+
+#[test]
+fn invalid_suffix() {
+    init();
+
+    std::env::set_var("INVALID_SUFFIX_COUNT", "5Q");
+
+    let a = econf::load(InvalidSuffix { count: 1 }, "invalid_suffix");
+    assert_eq!(a.count, 1);
+
+    assert!(logged(
+        Level::Error,
+        "econf: loading INVALID_SUFFIX_COUNT: error on parsing \"5Q\": unknown suffix \"q\" in \"5Q\""
+    ));
+}
+
+#[derive(LoadEnv)]
+struct Chars {
+    c1: char,
+    c2: char,
+    c3: char,
+}
+
+#[test]
+fn chars() {
+    init();
+
+    std::env::set_var("CHARS_C1", "ab");
+    std::env::set_var("CHARS_C2", "é");
+    std::env::set_var("CHARS_C3", "👍🏽");
+
+    let a = econf::load(
+        Chars {
+            c1: 'x',
+            c2: 'x',
+            c3: 'x',
+        },
+        "chars",
+    );
+    assert_eq!(a.c1, 'x');
+    assert_eq!(a.c2, 'é');
+    assert_eq!(a.c3, 'x');
+
+    assert!(logged(
+        Level::Error,
+        "econf: loading CHARS_C1: error on parsing \"ab\": expected single character, got 2"
+    ));
+    assert!(logged(
+        Level::Error,
+        "econf: loading CHARS_C3: error on parsing \"👍🏽\": expected single character, got 2"
+    ));
+}
@@ -0,0 +1,43 @@
+#![cfg(feature = "shlex")]
+
+use econf::LoadEnv;
+
+#[derive(LoadEnv)]
+struct ShellWords {
+    #[econf(shell_words)]
+    args: Vec<String>,
+    #[econf(shell_words)]
+    empty: Vec<String>,
+    #[econf(shell_words)]
+    broken: Vec<String>,
+}
+
+#[test]
+fn shell_words() {
+    std::env::set_var(
+        "SHELL_WORDS_ARGS",
+        r#"--foo 'bar baz' --name="a b" escaped\ space "say \"hi\"""#,
+    );
+    std::env::set_var("SHELL_WORDS_EMPTY", "");
+    std::env::set_var("SHELL_WORDS_BROKEN", "'unclosed");
+
+    let a = ShellWords {
+        args: vec![],
+        empty: vec!["x".into()],
+        broken: vec!["x".into()],
+    };
+
+    let a = econf::load(a, "shell_words");
+    assert_eq!(
+        a.args,
+        [
+            "--foo",
+            "bar baz",
+            "--name=a b",
+            "escaped space",
+            "say \"hi\""
+        ]
+    );
+    assert!(a.empty.is_empty());
+    assert_eq!(a.broken, ["x"]);
+}
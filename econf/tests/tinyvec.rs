@@ -0,0 +1,35 @@
+#![cfg(feature = "tinyvec")]
+
+use econf::LoadEnv;
+use tinyvec::{array_vec, tiny_vec, ArrayVec, TinyVec};
+
+#[derive(LoadEnv)]
+struct TinyVecs {
+    inline: TinyVec<[u32; 4]>,
+    spilled: TinyVec<[u32; 2]>,
+    array: ArrayVec<[u32; 2]>,
+    overflow: ArrayVec<[u32; 2]>,
+}
+
+#[test]
+fn tinyvec() {
+    std::env::set_var("TINYVEC_INLINE", "[1, 2, 3]");
+    std::env::set_var("TINYVEC_SPILLED", "[1, 2, 3]");
+    std::env::set_var("TINYVEC_ARRAY", "[4, 5]");
+    std::env::set_var("TINYVEC_OVERFLOW", "[1, 2, 3]");
+
+    let a = TinyVecs {
+        inline: tiny_vec!(),
+        spilled: tiny_vec!(),
+        array: array_vec!(),
+        overflow: array_vec!([u32; 2] => 9),
+    };
+
+    let a = econf::load(a, "tinyvec");
+    assert_eq!(a.inline.as_slice(), [1, 2, 3]);
+    assert!(a.inline.is_inline());
+    assert_eq!(a.spilled.as_slice(), [1, 2, 3]);
+    assert!(a.spilled.is_heap());
+    assert_eq!(a.array.as_slice(), [4, 5]);
+    assert_eq!(a.overflow.as_slice(), [9]);
+}
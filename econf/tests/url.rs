@@ -0,0 +1,30 @@
+#![cfg(feature = "url")]
+
+use econf::LoadEnv;
+use url::Url;
+
+#[derive(LoadEnv)]
+struct Urls {
+    endpoint: Url,
+    broken: Url,
+}
+
+#[test]
+fn url() {
+    std::env::set_var("URLS_ENDPOINT", "https://example.com:8443/api?v=1");
+    std::env::set_var("URLS_BROKEN", "not a url");
+
+    let fallback = Url::parse("http://localhost/").unwrap();
+    let a = Urls {
+        endpoint: fallback.clone(),
+        broken: fallback.clone(),
+    };
+
+    let a = econf::load(a, "urls");
+    assert_eq!(a.endpoint.scheme(), "https");
+    assert_eq!(a.endpoint.host_str(), Some("example.com"));
+    assert_eq!(a.endpoint.port(), Some(8443));
+    assert_eq!(a.endpoint.path(), "/api");
+    assert_eq!(a.endpoint.query(), Some("v=1"));
+    assert_eq!(a.broken, fallback);
+}